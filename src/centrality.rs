@@ -0,0 +1,136 @@
+use crate::db;
+use crate::graph::ActorGraph;
+use crate::link_finder::{actor_distances, UNREACHABLE};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rusqlite::{Connection, Result};
+
+// Up to this many actors, closeness is computed exactly with a BFS from every actor.
+pub const EXACT_CLOSENESS_MAX_ACTORS: usize = 5_000;
+// BFS sources used to estimate closeness on larger graphs.
+pub const DEFAULT_CLOSENESS_SAMPLES: usize = 1_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActorCentrality {
+    pub actor_id: i64,
+    pub degree: usize, // Distinct co-stars
+    pub closeness: f64, // 0..=1, higher is more central
+}
+
+// Degree and closeness for every actor with credits. Closeness uses the
+// Wasserman-Faust form, (r / (n - 1)) * (r / total distance) with r the actors
+// the actor can reach, so actors in small components don't score as central.
+// With `samples` below the actor count (or no `samples` on a large graph) the
+// BFS runs from that many random actors only, and each actor's reach and total
+// distance are estimated from the sampled sources that reach it.
+pub fn compute_centrality(graph: &ActorGraph, samples: Option<usize>, seed: u64) -> Vec<ActorCentrality> {
+    let actor_count = graph.actor_count();
+    let sample_size = match samples {
+        Some(samples) => samples.min(actor_count),
+        None if actor_count <= EXACT_CLOSENESS_MAX_ACTORS => actor_count,
+        None => DEFAULT_CLOSENESS_SAMPLES,
+    };
+    let sources: Vec<usize> = if sample_size == actor_count {
+        (0..actor_count).collect()
+    } else {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut sources = rand::seq::index::sample(&mut rng, actor_count, sample_size).into_vec();
+        sources.sort_unstable();
+        sources
+    };
+
+    // Distances are symmetric, so a BFS from each source gives every actor's distance to it
+    let mut total_distance = vec![0u64; actor_count];
+    let mut reached_by = vec![0usize; actor_count];
+    let mut is_source = vec![false; actor_count];
+    for &source in &sources {
+        is_source[source] = true;
+        for (actor, distance) in actor_distances(graph, source).into_iter().enumerate() {
+            if distance != UNREACHABLE && actor != source {
+                total_distance[actor] += distance as u64;
+                reached_by[actor] += 1;
+            }
+        }
+    }
+
+    let degrees = co_star_degrees(graph);
+    (0..actor_count)
+        .map(|actor| {
+            let other_sources = sources.len() - usize::from(is_source[actor]);
+            let closeness = if total_distance[actor] == 0 || other_sources == 0 {
+                0.0
+            } else {
+                let reached = reached_by[actor] as f64;
+                (reached / other_sources as f64) * (reached / total_distance[actor] as f64)
+            };
+            ActorCentrality { actor_id: graph.actor_id(actor), degree: degrees[actor], closeness }
+        })
+        .collect()
+}
+
+// Distinct co-stars per actor index.
+pub fn co_star_degrees(graph: &ActorGraph) -> Vec<usize> {
+    let mut last_counted_for = vec![usize::MAX; graph.actor_count()];
+    (0..graph.actor_count())
+        .map(|actor| {
+            let mut degree = 0;
+            for &movie in graph.movies_of(actor) {
+                for &co_star in graph.cast_of(movie as usize) {
+                    let co_star = co_star as usize;
+                    if co_star != actor && last_counted_for[co_star] != actor {
+                        last_counted_for[co_star] = actor;
+                        degree += 1;
+                    }
+                }
+            }
+            degree
+        })
+        .collect()
+}
+
+// Replaces the stored centrality with a fresh run.
+pub fn save_centrality(conn: &Connection, centrality: &[ActorCentrality]) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    db::clear_actor_centrality(&tx)?;
+    for actor in centrality {
+        db::insert_actor_centrality(&tx, actor.actor_id, actor.degree, actor.closeness)?;
+    }
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[test]
+    fn test_compute_centrality_exact() -> Result<()> {
+        let conn = test_support::fixture_connection()?;
+        let graph = ActorGraph::load(&conn)?;
+        let centrality = compute_centrality(&graph, None, 0);
+        let norton = centrality.iter().find(|actor| actor.actor_id == 1).unwrap();
+        // Six co-stars; reaches 10 of 12 other actors with distances summing to 16
+        assert_eq!(norton.degree, 6);
+        assert!((norton.closeness - (10.0 / 12.0) * (10.0 / 16.0)).abs() < 1e-9);
+
+        // Using every actor as a sample is the exact computation
+        assert_eq!(compute_centrality(&graph, Some(100), 7), centrality);
+        // Sampling is deterministic for a seed
+        assert_eq!(compute_centrality(&graph, Some(4), 7), compute_centrality(&graph, Some(4), 7));
+        Ok(())
+    }
+
+    #[test]
+    fn test_central_actors_leaderboard() -> Result<()> {
+        let conn = test_support::fixture_connection()?;
+        let graph = ActorGraph::load(&conn)?;
+        save_centrality(&conn, &compute_centrality(&graph, None, 0))?;
+        // Brad Pitt is at most two hops from everyone he can reach
+        let by_closeness = db::get_central_actors(&conn, false, 3)?;
+        assert_eq!(by_closeness.len(), 3);
+        assert_eq!(by_closeness[0].name, "Brad Pitt");
+        let by_degree = db::get_central_actors(&conn, true, 1)?;
+        assert_eq!(by_degree[0].name, "Edward Norton");
+        Ok(())
+    }
+}
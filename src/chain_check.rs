@@ -0,0 +1,157 @@
+use crate::graph::ActorGraph;
+use crate::link_finder::{find_actor_link_with_options, LinkConstraints, SearchOptions, SearchOutcome};
+use serde::Serialize;
+
+// One hop of a player's chain, with names already resolved. Names and titles aren't
+// unique, so a hop lists every actor with the given name and every movie with the
+// given title; any of them may link the hop.
+#[derive(Debug, Clone, Default)]
+pub struct SubmittedHop {
+    pub from_actor_ids: Vec<i64>, // Empty if the name matched no actor
+    pub movie_ids: Vec<i64>, // Empty if the title matched no movie
+    pub to_actor_ids: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HopProblem {
+    UnknownFromActor,
+    UnknownMovie,
+    UnknownToActor,
+    FromActorNotInMovie,
+    ToActorNotInMovie,
+}
+
+// The (start actor, end actor) pairs the whole chain links, or the index and problem
+// of the first hop that doesn't hold. An actor shared by two hops has to be the same
+// person in both.
+pub fn check_chain(graph: &ActorGraph, hops: &[SubmittedHop]) -> Result<Vec<(i64, i64)>, (usize, HopProblem)> {
+    let mut reached: Option<Vec<(i64, i64)>> = None; // (start actor, actor the previous hop ends on)
+    for (i, hop) in hops.iter().enumerate() {
+        let from_actor_ids: Vec<i64> = match &reached {
+            Some(reached) => hop.from_actor_ids.iter().copied().filter(|actor_id| reached.iter().any(|&(_, end)| end == *actor_id)).collect(),
+            None => hop.from_actor_ids.clone(),
+        };
+        let links = check_hop(graph, hop, &from_actor_ids).map_err(|problem| (i, problem))?;
+        let mut next: Vec<(i64, i64)> = match &reached {
+            Some(reached) => reached
+                .iter()
+                .flat_map(|&(start, end)| links.iter().filter(move |&&(from, _)| from == end).map(move |&(_, to)| (start, to)))
+                .collect(),
+            None => links,
+        };
+        next.sort_unstable();
+        next.dedup();
+        reached = Some(next);
+    }
+    Ok(reached.unwrap_or_default())
+}
+
+// The (from actor, to actor) pairs among `from_actor_ids` and the hop's to-actors that
+// share one of its movies.
+fn check_hop(graph: &ActorGraph, hop: &SubmittedHop, from_actor_ids: &[i64]) -> Result<Vec<(i64, i64)>, HopProblem> {
+    if hop.from_actor_ids.is_empty() {
+        return Err(HopProblem::UnknownFromActor);
+    }
+    if hop.movie_ids.is_empty() {
+        return Err(HopProblem::UnknownMovie);
+    }
+    if hop.to_actor_ids.is_empty() {
+        return Err(HopProblem::UnknownToActor);
+    }
+
+    let mut from_in_movie = false;
+    let mut links = Vec::new();
+    for &movie_id in &hop.movie_ids {
+        let from_in_this_movie: Vec<i64> = from_actor_ids.iter().copied().filter(|&actor_id| graph.appears_in(actor_id, movie_id)).collect();
+        if from_in_this_movie.is_empty() {
+            continue;
+        }
+        from_in_movie = true;
+        for &to_actor_id in hop.to_actor_ids.iter().filter(|&&actor_id| graph.appears_in(actor_id, movie_id)) {
+            links.extend(from_in_this_movie.iter().map(|&from_actor_id| (from_actor_id, to_actor_id)));
+        }
+    }
+    if !from_in_movie {
+        return Err(HopProblem::FromActorNotInMovie);
+    }
+    if links.is_empty() {
+        return Err(HopProblem::ToActorNotInMovie);
+    }
+    Ok(links)
+}
+
+// Fewest hops linking any of the (start actor, end actor) pairs.
+pub fn shortest_link_number(graph: &ActorGraph, pairs: &[(i64, i64)], options: &SearchOptions) -> SearchOutcome<usize> {
+    let mut shortest = None;
+    for &(start_actor_id, end_actor_id) in pairs {
+        match find_actor_link_with_options(graph, start_actor_id, end_actor_id, &LinkConstraints::default(), options) {
+            SearchOutcome::Found(path) => shortest = Some(shortest.map_or(path.len() - 1, |shortest: usize| shortest.min(path.len() - 1))),
+            SearchOutcome::NotFound => {}
+            SearchOutcome::Aborted(reason) => return SearchOutcome::Aborted(reason),
+        }
+    }
+    match shortest {
+        Some(link_number) => SearchOutcome::Found(link_number),
+        None => SearchOutcome::NotFound,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use rusqlite::Result;
+
+    fn hop(from_actor_id: i64, movie_ids: &[i64], to_actor_id: i64) -> SubmittedHop {
+        SubmittedHop { from_actor_ids: vec![from_actor_id], movie_ids: movie_ids.to_vec(), to_actor_ids: vec![to_actor_id] }
+    }
+
+    fn shared_name(from_actor_ids: &[i64], movie_ids: &[i64], to_actor_ids: &[i64]) -> SubmittedHop {
+        SubmittedHop { from_actor_ids: from_actor_ids.to_vec(), movie_ids: movie_ids.to_vec(), to_actor_ids: to_actor_ids.to_vec() }
+    }
+
+    #[test]
+    fn test_check_chain() -> Result<()> {
+        let conn = test_support::fixture_connection()?;
+        let graph = ActorGraph::load(&conn)?;
+        // Norton -(Fight Club)- Pitt -(Se7en)- Spacey
+        assert_eq!(check_chain(&graph, &[hop(1, &[1], 2), hop(2, &[2], 6)]), Ok(vec![(1, 6)]));
+        // Spacey isn't in Fight Club
+        assert_eq!(check_chain(&graph, &[hop(1, &[1], 2), hop(2, &[1], 6)]), Err((1, HopProblem::ToActorNotInMovie)));
+        assert_eq!(check_chain(&graph, &[hop(6, &[1], 2)]), Err((0, HopProblem::FromActorNotInMovie)));
+        // Any movie sharing the title will do
+        assert_eq!(check_chain(&graph, &[hop(2, &[3, 2], 6)]), Ok(vec![(2, 6)]));
+
+        let unknown_movie = SubmittedHop { movie_ids: Vec::new(), ..hop(1, &[], 2) };
+        assert_eq!(check_chain(&graph, &[unknown_movie]), Err((0, HopProblem::UnknownMovie)));
+        let unknown_actor = SubmittedHop { to_actor_ids: Vec::new(), ..hop(1, &[1], 2) };
+        assert_eq!(check_chain(&graph, &[hop(1, &[1], 2), unknown_actor]), Err((1, HopProblem::UnknownToActor)));
+
+        // A name shared by two actors fits if either of them does: "Brad Pitt" is 2 or 99
+        assert_eq!(check_chain(&graph, &[shared_name(&[1], &[1], &[99, 2]), shared_name(&[99, 2], &[2], &[6])]), Ok(vec![(1, 6)]));
+        // ...but both hops have to agree on which: Byrne could take the second hop, only Pitt the first
+        assert_eq!(
+            check_chain(&graph, &[shared_name(&[1], &[1], &[2, 7]), shared_name(&[2, 7], &[3], &[8])]),
+            Err((1, HopProblem::FromActorNotInMovie))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_link_number_for_shared_names() -> Result<()> {
+        let conn = test_support::fixture_connection()?;
+        let graph = ActorGraph::load(&conn)?;
+        let options = SearchOptions::default();
+        // "Brad Pitt" starts the chain and is Pitt (2) or Byrne (7); only Byrne is in The Usual Suspects
+        let pairs = check_chain(&graph, &[shared_name(&[2, 7], &[3], &[8])]).unwrap();
+        assert_eq!(pairs, vec![(7, 8)]);
+        // So the optimum is Byrne's one hop to del Toro, not Pitt's two
+        assert_eq!(shortest_link_number(&graph, &pairs, &options), SearchOutcome::Found(1));
+        assert_eq!(shortest_link_number(&graph, &[(2, 8)], &options), SearchOutcome::Found(2));
+        // Without a matched pair, the closest of the candidates counts
+        assert_eq!(shortest_link_number(&graph, &[(2, 8), (7, 8)], &options), SearchOutcome::Found(1));
+        assert_eq!(shortest_link_number(&graph, &[(1, 12)], &options), SearchOutcome::NotFound);
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use crate::graph::ActorGraph;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Collaborator {
+    pub actor_id: i64,
+    pub shared_movies: usize,
+}
+
+// An actor who never worked with the given actor but shares many of their co-stars.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SuggestedCollaborator {
+    pub actor_id: i64,
+    pub common_co_stars: usize,
+    pub jaccard: f64, // Common co-stars over the co-stars of either actor
+}
+
+// Co-stars ranked by how many movies they made with the actor. Empty if the actor has no credits.
+pub fn top_collaborators(graph: &ActorGraph, actor_id: i64, limit: usize) -> Vec<Collaborator> {
+    let Some(actor) = graph.actor_index(actor_id) else {
+        return Vec::new();
+    };
+    let mut shared_movies: HashMap<usize, usize> = HashMap::new();
+    for &movie in graph.movies_of(actor) {
+        for &co_star in graph.cast_of(movie as usize) {
+            if co_star as usize != actor {
+                *shared_movies.entry(co_star as usize).or_default() += 1;
+            }
+        }
+    }
+
+    let mut collaborators: Vec<Collaborator> = shared_movies
+        .into_iter()
+        .map(|(co_star, shared_movies)| Collaborator { actor_id: graph.actor_id(co_star), shared_movies })
+        .collect();
+    collaborators.sort_by(|a, b| b.shared_movies.cmp(&a.shared_movies).then(a.actor_id.cmp(&b.actor_id)));
+    collaborators.truncate(limit);
+    collaborators
+}
+
+// Actors two hops away ranked by the Jaccard similarity of their co-star sets with
+// the actor's, then by the number of co-stars they have in common. `co_star_degrees`
+// is `centrality::co_star_degrees` for the graph, computed once up front.
+pub fn suggested_collaborators(graph: &ActorGraph, co_star_degrees: &[usize], actor_id: i64, limit: usize) -> Vec<SuggestedCollaborator> {
+    let Some(actor) = graph.actor_index(actor_id) else {
+        return Vec::new();
+    };
+    let co_stars = co_stars_of(graph, actor);
+    // candidate -> (common co-stars, last co-star counted), so several movies with one co-star count once
+    let mut common_co_stars: HashMap<usize, (usize, usize)> = HashMap::new();
+    for &co_star in &co_stars {
+        for &movie in graph.movies_of(co_star) {
+            for &candidate in graph.cast_of(movie as usize) {
+                let candidate = candidate as usize;
+                if candidate == actor || co_stars.contains(&candidate) {
+                    continue;
+                }
+                let (common, last_co_star) = common_co_stars.entry(candidate).or_insert((0, usize::MAX));
+                if *last_co_star != co_star {
+                    *last_co_star = co_star;
+                    *common += 1;
+                }
+            }
+        }
+    }
+
+    let mut suggestions: Vec<SuggestedCollaborator> = common_co_stars
+        .into_iter()
+        .map(|(candidate, (common, _))| {
+            let union = co_stars.len() + co_star_degrees[candidate] - common;
+            SuggestedCollaborator { actor_id: graph.actor_id(candidate), common_co_stars: common, jaccard: common as f64 / union as f64 }
+        })
+        .collect();
+    suggestions.sort_by(|a, b| {
+        b.jaccard.total_cmp(&a.jaccard).then(b.common_co_stars.cmp(&a.common_co_stars)).then(a.actor_id.cmp(&b.actor_id))
+    });
+    suggestions.truncate(limit);
+    suggestions
+}
+
+// Distinct co-stars of an actor index, excluding the actor.
+fn co_stars_of(graph: &ActorGraph, actor: usize) -> HashSet<usize> {
+    graph
+        .movies_of(actor)
+        .iter()
+        .flat_map(|&movie| graph.cast_of(movie as usize))
+        .map(|&co_star| co_star as usize)
+        .filter(|&co_star| co_star != actor)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::centrality::co_star_degrees;
+    use crate::graph::Credit;
+    use crate::test_support;
+    use rusqlite::Result;
+
+    #[test]
+    fn test_top_collaborators() {
+        let credit = |movie_id, actor_id| Credit { movie_id, actor_id, cast_order: None };
+        // Actor 1 made two movies with actor 2 and one with actor 3
+        let graph = ActorGraph::from_credits(vec![credit(1, 1), credit(1, 2), credit(2, 1), credit(2, 2), credit(2, 3)]);
+        assert_eq!(
+            top_collaborators(&graph, 1, 10),
+            vec![Collaborator { actor_id: 2, shared_movies: 2 }, Collaborator { actor_id: 3, shared_movies: 1 }]
+        );
+        assert_eq!(top_collaborators(&graph, 1, 1).len(), 1);
+        assert!(top_collaborators(&graph, 999, 10).is_empty());
+    }
+
+    #[test]
+    fn test_suggested_collaborators() -> Result<()> {
+        let conn = test_support::fixture_connection()?;
+        let graph = ActorGraph::load(&conn)?;
+        // Edward Norton never worked with Morgan Freeman, but Pitt and Robbins did both
+        let suggestions = suggested_collaborators(&graph, &co_star_degrees(&graph), 1, 10);
+        let ids: Vec<i64> = suggestions.iter().map(|suggestion| suggestion.actor_id).collect();
+        assert_eq!(ids, vec![5, 6]);
+        assert_eq!(suggestions[0].common_co_stars, 2);
+        assert!((suggestions[0].jaccard - 2.0 / 7.0).abs() < 1e-9);
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+use crate::db;
+use crate::graph::ActorGraph;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rusqlite::{Connection, Result};
+use std::collections::HashMap;
+
+// Label propagation usually settles in a handful of rounds; this caps oscillation.
+pub const MAX_ITERATIONS: usize = 20;
+
+// Communities of the co-star graph found by label propagation: every actor
+// repeatedly takes the label most common among their co-stars, weighted by
+// shared movies, until labels stop changing. Community IDs are numbered by
+// descending size, so community 0 is always the largest.
+pub struct Communities {
+    labels: Vec<u32>, // Community ID per actor index
+    sizes: Vec<usize>, // Actors per community ID
+    internal_degrees: Vec<usize>, // Distinct co-stars in the same community, per actor index
+}
+
+impl Communities {
+    // The seed fixes the visiting order, which decides ties between equally common labels.
+    pub fn detect(graph: &ActorGraph, seed: u64) -> Communities {
+        let actor_count = graph.actor_count();
+        let mut labels: Vec<u32> = (0..actor_count as u32).collect();
+        let mut order: Vec<usize> = (0..actor_count).collect();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut label_weights: HashMap<u32, usize> = HashMap::new();
+
+        for _ in 0..MAX_ITERATIONS {
+            order.shuffle(&mut rng);
+            let mut changed = false;
+            for &actor in &order {
+                label_weights.clear();
+                for &movie in graph.movies_of(actor) {
+                    for &co_star in graph.cast_of(movie as usize) {
+                        if co_star as usize != actor {
+                            *label_weights.entry(labels[co_star as usize]).or_default() += 1;
+                        }
+                    }
+                }
+                let Some(&best_weight) = label_weights.values().max() else {
+                    continue; // No co-stars
+                };
+                // Keep the current label on a tie, otherwise take the smallest of the best
+                let current = labels[actor];
+                if label_weights.get(&current) == Some(&best_weight) {
+                    continue;
+                }
+                let best_label = label_weights.iter().filter(|&(_, &weight)| weight == best_weight).map(|(&label, _)| label).min().unwrap();
+                labels[actor] = best_label;
+                changed = true;
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        // Renumber largest first; ties go to the community whose first actor comes first
+        let mut first_seen: HashMap<u32, usize> = HashMap::new();
+        let mut sizes_by_label: Vec<(u32, usize)> = Vec::new();
+        for &label in &labels {
+            match first_seen.get(&label) {
+                Some(&position) => sizes_by_label[position].1 += 1,
+                None => {
+                    first_seen.insert(label, sizes_by_label.len());
+                    sizes_by_label.push((label, 1));
+                }
+            }
+        }
+        let mut by_size: Vec<usize> = (0..sizes_by_label.len()).collect();
+        by_size.sort_by_key(|&position| std::cmp::Reverse(sizes_by_label[position].1));
+        let mut renumbered: HashMap<u32, u32> = HashMap::new();
+        for (community_id, &position) in by_size.iter().enumerate() {
+            renumbered.insert(sizes_by_label[position].0, community_id as u32);
+        }
+        let labels: Vec<u32> = labels.iter().map(|label| renumbered[label]).collect();
+        let sizes = by_size.iter().map(|&position| sizes_by_label[position].1).collect();
+
+        let mut last_counted_for = vec![usize::MAX; actor_count];
+        let internal_degrees = (0..actor_count)
+            .map(|actor| {
+                let mut degree = 0;
+                for &movie in graph.movies_of(actor) {
+                    for &co_star in graph.cast_of(movie as usize) {
+                        let co_star = co_star as usize;
+                        if co_star != actor && labels[co_star] == labels[actor] && last_counted_for[co_star] != actor {
+                            last_counted_for[co_star] = actor;
+                            degree += 1;
+                        }
+                    }
+                }
+                degree
+            })
+            .collect();
+
+        Communities { labels, sizes, internal_degrees }
+    }
+
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    // (community ID, community size) for an actor index.
+    pub fn community_of(&self, actor: usize) -> (u32, usize) {
+        let label = self.labels[actor];
+        (label, self.sizes[label as usize])
+    }
+
+    // How many of the actor's co-stars are in the same community; the community's hubs have the most.
+    pub fn internal_degree(&self, actor: usize) -> usize {
+        self.internal_degrees[actor]
+    }
+}
+
+// Replaces the stored communities with a fresh run.
+pub fn save_communities(conn: &Connection, graph: &ActorGraph, communities: &Communities) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    db::clear_actor_communities(&tx)?;
+    for actor in 0..graph.actor_count() {
+        let (community_id, community_size) = communities.community_of(actor);
+        db::insert_actor_community(&tx, graph.actor_id(actor), community_id, community_size, communities.internal_degree(actor))?;
+    }
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Credit;
+    use crate::test_support;
+
+    #[test]
+    fn test_detect_communities() {
+        let credit = |movie_id, actor_id| Credit { movie_id, actor_id, cast_order: None };
+        // Actors 1-4 and 5-8 each made three movies together; one movie bridges actors 4 and 5
+        let mut credits = Vec::new();
+        for movie_id in 1..=3 {
+            credits.extend((1..=4).map(|actor_id| credit(movie_id, actor_id)));
+            credits.extend((5..=8).map(|actor_id| credit(movie_id + 10, actor_id)));
+        }
+        credits.extend([credit(20, 4), credit(20, 5)]);
+        let graph = ActorGraph::from_credits(credits);
+
+        let communities = Communities::detect(&graph, 1);
+        assert_eq!(communities.count(), 2);
+        let community = |actor_id| communities.community_of(graph.actor_index(actor_id).unwrap());
+        assert!((1..=4).all(|actor_id| community(actor_id) == community(1)));
+        assert!((5..=8).all(|actor_id| community(actor_id) == community(8)));
+        assert_ne!(community(1), community(8));
+        assert_eq!(community(1).1, 4);
+        assert_eq!(communities.internal_degree(graph.actor_index(4).unwrap()), 3);
+    }
+
+    #[test]
+    fn test_community_hubs() -> Result<()> {
+        let conn = test_support::fixture_connection()?;
+        let graph = ActorGraph::load(&conn)?;
+        let communities = Communities::detect(&graph, 0);
+        save_communities(&conn, &graph, &communities)?;
+
+        // The island actors form their own community
+        let island = db::get_actor_community(&conn, 12)?.unwrap();
+        assert_eq!(island.community_size, 2);
+        assert_ne!(db::get_actor_community(&conn, 1)?.unwrap().community_id, island.community_id);
+        let hubs = db::get_community_hubs(&conn, island.community_id, 5)?;
+        assert_eq!(hubs.iter().map(|hub| hub.actor_id).collect::<Vec<i64>>(), vec![12, 13]);
+        assert_eq!(hubs[0].internal_degree, 1);
+        Ok(())
+    }
+}
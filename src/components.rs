@@ -0,0 +1,137 @@
+use crate::db;
+use crate::graph::ActorGraph;
+use rusqlite::{Connection, Result};
+use serde::Serialize;
+
+// Connected components of the co-star graph. Component IDs are numbered by
+// descending size, so component 0 is always the largest.
+pub struct Components {
+    labels: Vec<u32>, // Component ID per actor index
+    sizes: Vec<usize>, // Actors per component ID
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComponentSizeCount {
+    pub size: usize,
+    pub components: usize,
+}
+
+impl Components {
+    pub fn compute(graph: &ActorGraph) -> Components {
+        let mut labels = vec![u32::MAX; graph.actor_count()];
+        let mut movies_seen = vec![false; graph.movie_count()];
+        let mut sizes = Vec::new();
+        let mut stack = Vec::new();
+        for root in 0..graph.actor_count() {
+            if labels[root] != u32::MAX {
+                continue;
+            }
+            let label = sizes.len() as u32;
+            labels[root] = label;
+            stack.push(root);
+            let mut size = 0;
+            while let Some(actor) = stack.pop() {
+                size += 1;
+                for &movie in graph.movies_of(actor) {
+                    if std::mem::replace(&mut movies_seen[movie as usize], true) {
+                        continue;
+                    }
+                    for &co_star in graph.cast_of(movie as usize) {
+                        if labels[co_star as usize] == u32::MAX {
+                            labels[co_star as usize] = label;
+                            stack.push(co_star as usize);
+                        }
+                    }
+                }
+            }
+            sizes.push(size);
+        }
+
+        // Renumber largest first; ties keep discovery order
+        let mut by_size: Vec<usize> = (0..sizes.len()).collect();
+        by_size.sort_by_key(|&label| std::cmp::Reverse(sizes[label]));
+        let mut renumbered = vec![0u32; sizes.len()];
+        for (new_label, &old_label) in by_size.iter().enumerate() {
+            renumbered[old_label] = new_label as u32;
+        }
+        Components {
+            labels: labels.into_iter().map(|label| renumbered[label as usize]).collect(),
+            sizes: by_size.into_iter().map(|label| sizes[label]).collect(),
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    pub fn largest_size(&self) -> usize {
+        self.sizes.first().copied().unwrap_or(0)
+    }
+
+    // (component ID, component size) for an actor index.
+    pub fn component_of(&self, actor: usize) -> (u32, usize) {
+        let label = self.labels[actor];
+        (label, self.sizes[label as usize])
+    }
+
+    // False only when both actors have credits and sit in different components, in
+    // which case no search can link them.
+    pub fn may_link(&self, graph: &ActorGraph, first_actor_id: i64, second_actor_id: i64) -> bool {
+        match (graph.actor_index(first_actor_id), graph.actor_index(second_actor_id)) {
+            (Some(first), Some(second)) => self.labels[first] == self.labels[second],
+            _ => true,
+        }
+    }
+
+    // How many components there are of each size, largest size first.
+    pub fn size_counts(&self) -> Vec<ComponentSizeCount> {
+        let mut counts: Vec<ComponentSizeCount> = Vec::new();
+        for &size in &self.sizes {
+            match counts.last_mut() {
+                Some(last) if last.size == size => last.components += 1,
+                _ => counts.push(ComponentSizeCount { size, components: 1 }),
+            }
+        }
+        counts
+    }
+}
+
+// Replaces the stored component labels with a fresh run.
+pub fn save_components(conn: &Connection, graph: &ActorGraph, components: &Components) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    db::clear_actor_components(&tx)?;
+    for actor in 0..graph.actor_count() {
+        let (component_id, component_size) = components.component_of(actor);
+        db::insert_actor_component(&tx, graph.actor_id(actor), component_id, component_size)?;
+    }
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[test]
+    fn test_components() -> Result<()> {
+        let conn = test_support::fixture_connection()?;
+        let graph = ActorGraph::load(&conn)?;
+        let components = Components::compute(&graph);
+        // Eleven linked actors plus the two-actor island
+        assert_eq!(components.count(), 2);
+        assert_eq!(components.largest_size(), 11);
+        assert_eq!(components.component_of(graph.actor_index(12).unwrap()), (1, 2));
+        assert!(components.may_link(&graph, 1, 7));
+        assert!(!components.may_link(&graph, 1, 12));
+        assert!(components.may_link(&graph, 1, 999));
+        assert_eq!(
+            components.size_counts(),
+            vec![ComponentSizeCount { size: 11, components: 1 }, ComponentSizeCount { size: 2, components: 1 }]
+        );
+
+        save_components(&conn, &graph, &components)?;
+        let stored: i64 = conn.query_row("SELECT COUNT(*) FROM actor_components WHERE component_id = 0", [], |row| row.get(0))?;
+        assert_eq!(stored, 11);
+        Ok(())
+    }
+}
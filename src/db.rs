@@ -1,4 +1,7 @@
-use rusqlite::{Connection, Result};
+use crate::name_normalize::{edit_distance_within, normalize_name};
+use rusqlite::types::Null;
+use rusqlite::{params_from_iter, Connection, Result};
+use serde::Serialize;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
@@ -6,13 +9,246 @@ pub fn establish_connection() -> Result<Connection> {
     Connection::open("actor_link.db")
 }
 
+// Schema changes in order: migration i takes a database from version i to i + 1,
+// tracked in `PRAGMA user_version`. Released migrations must never change; add a new one.
+const MIGRATIONS: &[fn(&Connection) -> Result<()>] = &[
+    migrate_v1_base_schema,
+    migrate_v2_movie_metadata,
+    migrate_v3_graph_analysis,
+    migrate_v4_unique_credits,
+    migrate_v5_lookup_indexes,
+    migrate_v6_credit_characters,
+    migrate_v7_movie_details,
+    migrate_v8_actor_details,
+    migrate_v9_normalized_names,
+    migrate_v10_search_index,
+    migrate_v11_fuzzy_name_index,
+    migrate_v12_credit_counts,
+    migrate_v13_credit_roles,
+];
+
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
 pub fn setup_database(conn: &Connection) -> Result<()> {
+    migrate(conn)?;
+    Ok(())
+}
+
+pub fn schema_version(conn: &Connection) -> Result<u32> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+// Applies every pending migration, each in its own transaction, and returns the
+// version the database was at before. Databases from a newer build are left alone.
+pub fn migrate(conn: &Connection) -> Result<u32> {
+    let initial_version = schema_version(conn)?;
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(initial_version as usize) {
+        let tx = conn.unchecked_transaction()?;
+        migration(&tx)?;
+        tx.pragma_update(None, "user_version", version as u32 + 1)?;
+        tx.commit()?;
+    }
+    Ok(initial_version)
+}
+
+// Databases created before migrations existed are at version 0 but may already
+// have some of the later columns, so column additions have to check first.
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("SELECT 1 FROM pragma_table_info('{}') WHERE name = ?", table))?;
+    if !stmt.exists([column])? {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), ())?;
+    }
+    Ok(())
+}
+
+fn migrate_v1_base_schema(conn: &Connection) -> Result<()> {
     create_actor_table(conn)?;
     create_movie_table(conn)?;
     create_movie_actors_table(conn)?;
     Ok(())
 }
 
+// Release dates, popularity, genres and billing order for filtering and weighting searches
+fn migrate_v2_movie_metadata(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "movies", "release_date", "TEXT")?;
+    add_column_if_missing(conn, "movies", "popularity", "REAL")?;
+    add_column_if_missing(conn, "movie_actors", "cast_order", "INTEGER")?;
+    create_genre_tables(conn)?;
+    Ok(())
+}
+
+// Results of the graph_stats batch jobs
+fn migrate_v3_graph_analysis(conn: &Connection) -> Result<()> {
+    create_actor_centrality_table(conn)?;
+    create_actor_components_table(conn)?;
+    create_actor_communities_table(conn)?;
+    Ok(())
+}
+
+// Re-running db_populate used to insert every credit again. Duplicates collapse into the
+// oldest row, which keeps the best billing any of them had.
+fn migrate_v4_unique_credits(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "UPDATE movie_actors SET cast_order = (
+            SELECT MIN(other.cast_order) FROM movie_actors AS other
+            WHERE other.movie_id = movie_actors.movie_id AND other.actor_id = movie_actors.actor_id
+        )
+        WHERE (movie_id, actor_id) IN (
+            SELECT movie_id, actor_id FROM movie_actors GROUP BY movie_id, actor_id HAVING COUNT(*) > 1
+        );
+        DELETE FROM movie_actors WHERE movie_actor_id NOT IN (
+            SELECT MIN(movie_actor_id) FROM movie_actors GROUP BY movie_id, actor_id
+        );
+        CREATE UNIQUE INDEX IF NOT EXISTS movie_actors_unique_credit ON movie_actors (movie_id, actor_id);",
+    )
+}
+
+// Searches walk credits in both directions and requests resolve names and titles,
+// all of which were full table scans. The unique (movie_id, actor_id) index from v4
+// already covers the movie -> actors direction.
+fn migrate_v5_lookup_indexes(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS movie_actors_by_actor ON movie_actors (actor_id, movie_id);
+        CREATE INDEX IF NOT EXISTS actors_by_name ON actors (name);
+        CREATE INDEX IF NOT EXISTS movies_by_title ON movies (title);",
+    )
+}
+
+// The role each actor played, to show alongside the movies in a link
+fn migrate_v6_credit_characters(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "movie_actors", "character_name", "TEXT")
+}
+
+// Vote count, runtime and poster so clients can show richer movie cards
+fn migrate_v7_movie_details(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "movies", "vote_count", "INTEGER")?;
+    add_column_if_missing(conn, "movies", "runtime", "INTEGER")?;
+    add_column_if_missing(conn, "movies", "poster_path", "TEXT")?;
+    Ok(())
+}
+
+// Filled by the optional `db_populate --enrich-actors` pass; details_fetched_at is
+// NULL until an actor has been looked up.
+fn migrate_v8_actor_details(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "actors", "profile_path", "TEXT")?;
+    add_column_if_missing(conn, "actors", "popularity", "REAL")?;
+    add_column_if_missing(conn, "actors", "gender", "INTEGER")?;
+    add_column_if_missing(conn, "actors", "birthday", "TEXT")?;
+    add_column_if_missing(conn, "actors", "deathday", "TEXT")?;
+    add_column_if_missing(conn, "actors", "place_of_birth", "TEXT")?;
+    add_column_if_missing(conn, "actors", "details_fetched_at", "TEXT")?;
+    Ok(())
+}
+
+// Names folded by `normalize_name` so lookups can ignore case, accents and punctuation
+fn migrate_v9_normalized_names(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "actors", "name_normalized", "TEXT")?;
+    let mut select = conn.prepare("SELECT actor_id, name FROM actors")?;
+    let mut update = conn.prepare("UPDATE actors SET name_normalized = ? WHERE actor_id = ?")?;
+    let mut rows = select.query([])?;
+    while let Some(row) = rows.next()? {
+        let name: String = row.get(1)?;
+        update.execute((normalize_name(&name), row.get::<_, i64>(0)?))?;
+    }
+    conn.execute("CREATE INDEX IF NOT EXISTS actors_by_normalized_name ON actors (name_normalized)", ())?;
+    Ok(())
+}
+
+// FTS5 indexes over actor names and movie titles for typeahead. They read their text
+// from the base tables, and triggers keep them in step with every insert, update and delete.
+// FTS5 comes from the SQLite that rusqlite's `bundled` feature compiles in, not the system's.
+fn migrate_v10_search_index(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS actor_search USING fts5(
+            name, content = 'actors', content_rowid = 'actor_id',
+            tokenize = 'unicode61 remove_diacritics 2', prefix = '2 3'
+        );
+        CREATE TRIGGER IF NOT EXISTS actor_search_insert AFTER INSERT ON actors BEGIN
+            INSERT INTO actor_search (rowid, name) VALUES (new.actor_id, new.name);
+        END;
+        CREATE TRIGGER IF NOT EXISTS actor_search_delete AFTER DELETE ON actors BEGIN
+            INSERT INTO actor_search (actor_search, rowid, name) VALUES ('delete', old.actor_id, old.name);
+        END;
+        CREATE TRIGGER IF NOT EXISTS actor_search_update AFTER UPDATE OF name ON actors BEGIN
+            INSERT INTO actor_search (actor_search, rowid, name) VALUES ('delete', old.actor_id, old.name);
+            INSERT INTO actor_search (rowid, name) VALUES (new.actor_id, new.name);
+        END;
+        INSERT INTO actor_search (actor_search) VALUES ('rebuild');
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS movie_search USING fts5(
+            title, content = 'movies', content_rowid = 'movie_id',
+            tokenize = 'unicode61 remove_diacritics 2', prefix = '2 3'
+        );
+        CREATE TRIGGER IF NOT EXISTS movie_search_insert AFTER INSERT ON movies BEGIN
+            INSERT INTO movie_search (rowid, title) VALUES (new.movie_id, new.title);
+        END;
+        CREATE TRIGGER IF NOT EXISTS movie_search_delete AFTER DELETE ON movies BEGIN
+            INSERT INTO movie_search (movie_search, rowid, title) VALUES ('delete', old.movie_id, old.title);
+        END;
+        CREATE TRIGGER IF NOT EXISTS movie_search_update AFTER UPDATE OF title ON movies BEGIN
+            INSERT INTO movie_search (movie_search, rowid, title) VALUES ('delete', old.movie_id, old.title);
+            INSERT INTO movie_search (rowid, title) VALUES (new.movie_id, new.title);
+        END;
+        INSERT INTO movie_search (movie_search) VALUES ('rebuild');",
+    )
+}
+
+// Narrows the typo fallback in `find_actors_near_normalized_name` to names that start
+// with the same letter and have a similar length, instead of every name of that length.
+fn migrate_v11_fuzzy_name_index(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS actors_by_initial_and_length ON actors (substr(name_normalized, 1, 1), length(name_normalized))",
+        (),
+    )?;
+    Ok(())
+}
+
+// Credits per actor and per movie, so search can rank by them without counting
+// movie_actors for every hit. Triggers keep them in step with movie_actors; upserts
+// that only change billing or roles don't touch them.
+fn migrate_v12_credit_counts(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "actors", "credit_count", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "movies", "credit_count", "INTEGER NOT NULL DEFAULT 0")?;
+    conn.execute_batch(
+        "UPDATE actors SET credit_count = (SELECT COUNT(*) FROM movie_actors ma WHERE ma.actor_id = actors.actor_id);
+        UPDATE movies SET credit_count = (SELECT COUNT(*) FROM movie_actors ma WHERE ma.movie_id = movies.movie_id);
+        CREATE TRIGGER IF NOT EXISTS credit_count_insert AFTER INSERT ON movie_actors BEGIN
+            UPDATE actors SET credit_count = credit_count + 1 WHERE actor_id = new.actor_id;
+            UPDATE movies SET credit_count = credit_count + 1 WHERE movie_id = new.movie_id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS credit_count_delete AFTER DELETE ON movie_actors BEGIN
+            UPDATE actors SET credit_count = credit_count - 1 WHERE actor_id = old.actor_id;
+            UPDATE movies SET credit_count = credit_count - 1 WHERE movie_id = old.movie_id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS credit_count_update AFTER UPDATE OF movie_id, actor_id ON movie_actors BEGIN
+            UPDATE actors SET credit_count = credit_count - 1 WHERE actor_id = old.actor_id;
+            UPDATE movies SET credit_count = credit_count - 1 WHERE movie_id = old.movie_id;
+            UPDATE actors SET credit_count = credit_count + 1 WHERE actor_id = new.actor_id;
+            UPDATE movies SET credit_count = credit_count + 1 WHERE movie_id = new.movie_id;
+        END;",
+    )
+}
+
+// One row per role an actor played in a movie, since TMDB lists an actor once per role
+// and role names can themselves contain " / ". Replaces movie_actors.character_name,
+// which is no longer written; roles it merged before can't be told apart, so each
+// stored value becomes one role.
+fn migrate_v13_credit_roles(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS credit_roles (
+            movie_id        INTEGER NOT NULL,
+            actor_id        INTEGER NOT NULL,
+            character_name  TEXT NOT NULL,
+            FOREIGN KEY (movie_id) REFERENCES movies(movie_id),
+            FOREIGN KEY (actor_id) REFERENCES actors(actor_id)
+        );
+        CREATE UNIQUE INDEX IF NOT EXISTS credit_roles_unique_role ON credit_roles (movie_id, actor_id, character_name);
+        CREATE INDEX IF NOT EXISTS credit_roles_by_credit ON credit_roles (movie_id, actor_id);
+        INSERT OR IGNORE INTO credit_roles (movie_id, actor_id, character_name)
+            SELECT movie_id, actor_id, character_name FROM movie_actors WHERE character_name IS NOT NULL ORDER BY movie_actor_id;",
+    )
+}
+
 fn create_actor_table(conn: &Connection) -> Result<()> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS actors (
@@ -52,6 +288,72 @@ fn create_movie_actors_table(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+// genre_id is TMDB's own genre ID (e.g. 18 = Drama), which is stable across movies
+fn create_genre_tables(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS genres (
+            genre_id        INTEGER PRIMARY KEY,
+            name            TEXT NOT NULL
+        )",
+        (), // empty parameters
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS movie_genres (
+            movie_id        INTEGER NOT NULL,
+            genre_id        INTEGER NOT NULL,
+            PRIMARY KEY (movie_id, genre_id),
+            FOREIGN KEY (movie_id) REFERENCES movies(movie_id),
+            FOREIGN KEY (genre_id) REFERENCES genres(genre_id)
+        )",
+        (), // empty parameters
+    )?;
+    Ok(())
+}
+
+// Filled by the `graph_stats centrality` batch job; closeness may be a sampled estimate
+fn create_actor_centrality_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS actor_centrality (
+            actor_id        INTEGER PRIMARY KEY,
+            degree          INTEGER NOT NULL,
+            closeness       REAL NOT NULL,
+            FOREIGN KEY (actor_id) REFERENCES actors(actor_id)
+        )",
+        (), // empty parameters
+    )?;
+    Ok(())
+}
+
+// Filled by the `graph_stats components` batch job; component 0 is the largest
+fn create_actor_components_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS actor_components (
+            actor_id        INTEGER PRIMARY KEY,
+            component_id    INTEGER NOT NULL,
+            component_size  INTEGER NOT NULL,
+            FOREIGN KEY (actor_id) REFERENCES actors(actor_id)
+        )",
+        (), // empty parameters
+    )?;
+    Ok(())
+}
+
+// Filled by the `graph_stats communities` batch job; community 0 is the largest.
+// internal_degree counts co-stars in the same community, so a community's hubs have the highest.
+fn create_actor_communities_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS actor_communities (
+            actor_id        INTEGER PRIMARY KEY,
+            community_id    INTEGER NOT NULL,
+            community_size  INTEGER NOT NULL,
+            internal_degree INTEGER NOT NULL,
+            FOREIGN KEY (actor_id) REFERENCES actors(actor_id)
+        )",
+        (), // empty parameters
+    )?;
+    Ok(())
+}
+
 pub fn get_movie_count(conn: &Connection) -> Result<i64> {
     let mut stmt = conn.prepare("SELECT COUNT(*) FROM movies")?;
     let mut rows = stmt.query([])?;
@@ -61,30 +363,108 @@ pub fn get_movie_count(conn: &Connection) -> Result<i64> {
 
 pub fn insert_actor(conn: &Connection, tmdb_actor_id: u32, name: &str, known_for_department: &str) -> Result<i64> {
     conn.execute(
-        "INSERT OR IGNORE INTO actors (tmdb_actor_id, name, known_for_department) VALUES (?, ?, ?)",
-        (tmdb_actor_id, name, known_for_department),
+        "INSERT OR IGNORE INTO actors (tmdb_actor_id, name, known_for_department, name_normalized) VALUES (?, ?, ?, ?)",
+        (tmdb_actor_id, name, known_for_department, normalize_name(name)),
     )?;
     Ok(conn.last_insert_rowid())
 }
 
-pub fn insert_movie(conn: &Connection, tmdb_movie_id: u32, title: &str) -> Result<()> {
+#[derive(Default)]
+pub struct NewMovie<'a> {
+    pub tmdb_movie_id: u32,
+    pub title: &'a str,
+    pub release_date: Option<&'a str>, // "YYYY-MM-DD"
+    pub popularity: Option<f64>,
+    pub vote_count: Option<u32>,
+    pub runtime: Option<u32>, // Minutes
+    pub poster_path: Option<&'a str>,
+}
+
+// Inserting a movie again refreshes its details but keeps its movie_id.
+pub fn insert_movie(conn: &Connection, movie: &NewMovie) -> Result<()> {
     conn.execute(
-        "INSERT OR IGNORE INTO movies (tmdb_movie_id, title) VALUES (?, ?)",
-        (tmdb_movie_id, title),
+        "INSERT INTO movies (tmdb_movie_id, title, release_date, popularity, vote_count, runtime, poster_path)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT (tmdb_movie_id) DO UPDATE SET
+            title = excluded.title,
+            release_date = excluded.release_date,
+            popularity = excluded.popularity,
+            vote_count = excluded.vote_count,
+            runtime = excluded.runtime,
+            poster_path = excluded.poster_path",
+        (movie.tmdb_movie_id, movie.title, movie.release_date, movie.popularity, movie.vote_count, movie.runtime, movie.poster_path),
     )?;
     Ok(())
 }
 
-pub fn insert_movie_actor_link(conn: &Connection, movie_id: i64, actor_id: i64) -> Result<()> {
+pub fn insert_genre(conn: &Connection, genre_id: u32, name: &str) -> Result<()> {
     conn.execute(
-        "INSERT INTO movie_actors (movie_id, actor_id) VALUES (?, ?)",
-        (movie_id, actor_id),
+        "INSERT OR IGNORE INTO genres (genre_id, name) VALUES (?, ?)",
+        (genre_id, name),
     )?;
     Ok(())
 }
 
+pub fn insert_movie_genre(conn: &Connection, movie_id: i64, genre_id: u32) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO movie_genres (movie_id, genre_id) VALUES (?, ?)",
+        (movie_id, genre_id),
+    )?;
+    Ok(())
+}
+
+// cast_order is TMDB's billing position, 0 being top billed. Inserting a credit again
+// keeps one row with the better billing, so repeated ingests converge. TMDB lists an
+// actor once per role, so a new character is added to the roles already stored.
+pub fn insert_movie_actor_link(conn: &Connection, movie_id: i64, actor_id: i64, cast_order: Option<u32>, character_name: Option<&str>) -> Result<()> {
+    conn.execute(
+        "INSERT INTO movie_actors (movie_id, actor_id, cast_order) VALUES (?, ?, ?)
+        ON CONFLICT (movie_id, actor_id) DO UPDATE SET
+            cast_order = MIN(COALESCE(cast_order, excluded.cast_order), COALESCE(excluded.cast_order, cast_order))",
+        (movie_id, actor_id, cast_order),
+    )?;
+    if let Some(character_name) = character_name {
+        conn.execute(
+            "INSERT OR IGNORE INTO credit_roles (movie_id, actor_id, character_name) VALUES (?, ?, ?)",
+            (movie_id, actor_id, character_name),
+        )?;
+    }
+    Ok(())
+}
+
+const ACTOR_ID_BY_NAME: &str = "SELECT actor_id FROM actors WHERE name = ?";
+const MOVIE_ID_BY_TITLE: &str = "SELECT movie_id FROM movies WHERE title = ? ORDER BY movie_id LIMIT 1";
+const MOVIE_IDS_BY_TITLE: &str = "SELECT movie_id FROM movies WHERE title = ? ORDER BY movie_id";
+const MOVIE_IDS_FOR_ACTOR: &str = "SELECT movie_id FROM movie_actors WHERE actor_id = ?";
+const ACTOR_IDS_FOR_MOVIE: &str = "SELECT actor_id FROM movie_actors WHERE movie_id = ?";
+const ACTORS_BY_NAME: &str = "SELECT actor_id, name FROM actors WHERE name = ? ORDER BY actor_id";
+const ACTORS_BY_NORMALIZED_NAME: &str = "SELECT actor_id, name FROM actors WHERE name_normalized = ? ORDER BY actor_id";
+const ACTORS_NEAR_NORMALIZED_NAME: &str = "SELECT actor_id, name, name_normalized FROM actors
+    WHERE substr(name_normalized, 1, 1) = ? AND length(name_normalized) BETWEEN ? AND ?";
+const ACTOR_ID_BY_TMDB_ID: &str = "SELECT actor_id FROM actors WHERE tmdb_actor_id = ?";
+const KNOWN_FOR_MOVIES: &str = "SELECT m.movie_id, m.title, m.release_date FROM movie_actors ma
+    JOIN movies m ON m.movie_id = ma.movie_id
+    WHERE ma.actor_id = ?
+    ORDER BY COALESCE(m.popularity, 0) DESC, COALESCE(ma.cast_order, 1000000), m.movie_id LIMIT ?";
+const CREDIT_COUNT: &str = "SELECT COUNT(*) FROM movie_actors WHERE actor_id = ?";
+const CHARACTER_NAMES: &str = "SELECT character_name FROM credit_roles WHERE movie_id = ? AND actor_id = ? ORDER BY rowid";
+
+// The lookups requests make when resolving names, titles and IDs and describing
+// results, checked by `lookup_query_plans`. Searches themselves run on the in-memory graph.
+const LOOKUP_QUERIES: &[(&str, &str)] = &[
+    ("actors_by_name", ACTORS_BY_NAME),
+    ("actors_by_normalized_name", ACTORS_BY_NORMALIZED_NAME),
+    ("actors_near_normalized_name", ACTORS_NEAR_NORMALIZED_NAME),
+    ("actor_id_by_tmdb_id", ACTOR_ID_BY_TMDB_ID),
+    ("movie_id_by_title", MOVIE_ID_BY_TITLE),
+    ("movie_ids_by_title", MOVIE_IDS_BY_TITLE),
+    ("known_for_movies", KNOWN_FOR_MOVIES),
+    ("credit_count", CREDIT_COUNT),
+    ("character_names", CHARACTER_NAMES),
+];
+
 pub fn get_actor_id_by_name(conn: &Connection, actor_name: &str) -> Result<Option<i64>> {
-    let mut stmt = conn.prepare("SELECT actor_id FROM actors WHERE name = ?")?;
+    let mut stmt = conn.prepare(ACTOR_ID_BY_NAME)?;
     let mut rows = stmt.query([actor_name])?;
 
     if let Some(row) = rows.next()? {
@@ -95,6 +475,89 @@ pub fn get_actor_id_by_name(conn: &Connection, actor_name: &str) -> Result<Optio
     }
 }
 
+// Every actor with exactly this name, as (actor_id, name).
+pub fn get_actors_by_name(conn: &Connection, actor_name: &str) -> Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare(ACTORS_BY_NAME)?;
+    let rows = stmt.query_map([actor_name], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+// Every actor whose name normalizes to `normalized_name`, as (actor_id, name).
+pub fn get_actors_by_normalized_name(conn: &Connection, normalized_name: &str) -> Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare(ACTORS_BY_NORMALIZED_NAME)?;
+    let rows = stmt.query_map([normalized_name], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+// Actors whose normalized name starts with the same letter and is within `max_distance`
+// edits, as (actor_id, name, distance). A typo in the first letter isn't found.
+pub fn find_actors_near_normalized_name(conn: &Connection, normalized_name: &str, max_distance: usize) -> Result<Vec<(i64, String, usize)>> {
+    let Some(initial) = normalized_name.chars().next() else {
+        return Ok(Vec::new());
+    };
+    let length = normalized_name.chars().count();
+    let mut stmt = conn.prepare(ACTORS_NEAR_NORMALIZED_NAME)?;
+    let mut rows = stmt.query((initial.to_string(), length.saturating_sub(max_distance), length + max_distance))?;
+    let mut actors = Vec::new();
+    while let Some(row) = rows.next()? {
+        let candidate: String = row.get(2)?;
+        if let Some(distance) = edit_distance_within(normalized_name, &candidate, max_distance) {
+            actors.push((row.get(0)?, row.get(1)?, distance));
+        }
+    }
+    Ok(actors)
+}
+
+pub fn get_actor_id_by_tmdb_id(conn: &Connection, tmdb_actor_id: u32) -> Result<Option<i64>> {
+    let mut stmt = conn.prepare(ACTOR_ID_BY_TMDB_ID)?;
+    let mut rows = stmt.query([tmdb_actor_id])?;
+
+    if let Some(row) = rows.next()? {
+        Ok(Some(row.get(0)?))
+    } else {
+        Ok(None) // Actor not found
+    }
+}
+
+pub struct KnownForMovie {
+    pub movie_id: i64,
+    pub title: String,
+    pub release_date: Option<String>,
+}
+
+// An actor's best-known movies: the most popular ones they were billed highest in.
+pub fn get_known_for_movies(conn: &Connection, actor_id: i64, limit: usize) -> Result<Vec<KnownForMovie>> {
+    let mut stmt = conn.prepare(KNOWN_FOR_MOVIES)?;
+    let rows = stmt.query_map((actor_id, limit as i64), |row| {
+        Ok(KnownForMovie { movie_id: row.get(0)?, title: row.get(1)?, release_date: row.get(2)? })
+    })?;
+    rows.collect()
+}
+
+pub fn get_credit_count(conn: &Connection, actor_id: i64) -> Result<usize> {
+    conn.query_row(CREDIT_COUNT, [actor_id], |row| row.get(0))
+}
+
+// Titles aren't unique (remakes share them); the earliest inserted movie wins.
+pub fn get_movie_id_by_title(conn: &Connection, title: &str) -> Result<Option<i64>> {
+    let mut stmt = conn.prepare(MOVIE_ID_BY_TITLE)?;
+    let mut rows = stmt.query([title])?;
+
+    if let Some(row) = rows.next()? {
+        let movie_id: i64 = row.get(0)?;
+        Ok(Some(movie_id))
+    } else {
+        Ok(None) // Movie not found
+    }
+}
+
+// Every movie with exactly this title, oldest entry first.
+pub fn get_movie_ids_by_title(conn: &Connection, title: &str) -> Result<Vec<i64>> {
+    let mut stmt = conn.prepare(MOVIE_IDS_BY_TITLE)?;
+    let rows = stmt.query_map([title], |row| row.get(0))?;
+    rows.collect()
+}
+
 pub fn get_actor_name_by_id(conn: &Connection, actor_id: i64) -> Result<Option<String>> {
     let mut stmt = conn.prepare("SELECT name FROM actors WHERE actor_id = ?")?;
     let mut rows = stmt.query([actor_id])?;
@@ -119,9 +582,50 @@ pub fn get_movie_titles_by_ids(conn: &Connection, movie_ids: &HashSet<i64>) -> R
     Ok(movie_titles)
 }
 
+pub struct MovieMetadata {
+    pub movie_id: i64,
+    pub title: String,
+    pub release_date: Option<String>,
+    pub genres: Vec<String>, // In TMDB's order, main genre first
+    pub popularity: Option<f64>,
+    pub vote_count: Option<u32>,
+    pub runtime: Option<u32>,
+    pub poster_path: Option<String>,
+}
+
+// Title, release date, genres and the other stored details for each movie found.
+pub fn get_movie_metadata_by_ids(conn: &Connection, movie_ids: &HashSet<i64>) -> Result<HashMap<i64, MovieMetadata>> {
+    let mut movie_stmt = conn.prepare(
+        "SELECT title, release_date, popularity, vote_count, runtime, poster_path FROM movies WHERE movie_id = ?",
+    )?;
+    // movie_genres rows are inserted in the order TMDB lists the genres
+    let mut genre_stmt = conn.prepare(
+        "SELECT g.name FROM movie_genres mg JOIN genres g ON g.genre_id = mg.genre_id
+         WHERE mg.movie_id = ? ORDER BY mg.rowid",
+    )?;
+    let mut movies = HashMap::new();
+    for &movie_id in movie_ids {
+        let mut rows = movie_stmt.query([movie_id])?;
+        if let Some(row) = rows.next()? {
+            let genres = genre_stmt.query_map([movie_id], |genre_row| genre_row.get(0))?.collect::<Result<Vec<String>>>()?;
+            movies.insert(movie_id, MovieMetadata {
+                movie_id,
+                title: row.get(0)?,
+                release_date: row.get(1)?,
+                genres,
+                popularity: row.get(2)?,
+                vote_count: row.get(3)?,
+                runtime: row.get(4)?,
+                poster_path: row.get(5)?,
+            });
+        }
+    }
+    Ok(movies)
+}
+
 // New function to get movie IDs by actor ID
 pub fn get_movie_ids_for_actor(conn: &Connection, actor_id: i64) -> Result<HashSet<i64>> {
-    let mut stmt = conn.prepare("SELECT movie_id FROM movie_actors WHERE actor_id = ?")?;
+    let mut stmt = conn.prepare(MOVIE_IDS_FOR_ACTOR)?;
     let mut rows = stmt.query([actor_id])?;
     let mut movie_ids = HashSet::new();
     while let Some(row) = rows.next()? {
@@ -132,11 +636,554 @@ pub fn get_movie_ids_for_actor(conn: &Connection, actor_id: i64) -> Result<HashS
 
 // New function to get actor IDs by movie ID
 pub fn get_actor_ids_for_movie(conn: &Connection, movie_id: i64) -> Result<HashSet<i64>> {
-    let mut stmt = conn.prepare("SELECT actor_id FROM movie_actors WHERE movie_id = ?")?;
+    let mut stmt = conn.prepare(ACTOR_IDS_FOR_MOVIE)?;
     let mut rows = stmt.query([movie_id])?;
     let mut actor_ids = HashSet::new();
     while let Some(row) = rows.next()? {
         actor_ids.insert(row.get(0)?);
     }
     Ok(actor_ids)
-}
\ No newline at end of file
+}
+
+// The roles an actor played in a movie, in the order they were stored; empty if TMDB
+// didn't know.
+pub fn get_character_names(conn: &Connection, movie_id: i64, actor_id: i64) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(CHARACTER_NAMES)?;
+    let rows = stmt.query_map([movie_id, actor_id], |row| row.get(0))?;
+    rows.collect()
+}
+
+// "Name (Character)" for an actor's credit in a movie, or just the name if the role isn't
+// known. Several roles are listed as "Name (First / Second)".
+pub fn actor_name_with_character(conn: &Connection, actor_name: &str, movie_id: i64, actor_id: i64) -> Result<String> {
+    let characters = get_character_names(conn, movie_id, actor_id)?;
+    Ok(if characters.is_empty() {
+        actor_name.to_string()
+    } else {
+        format!("{} ({})", actor_name, characters.join(" / "))
+    })
+}
+
+// FTS5 query matching names that contain a word starting with each typed word, in
+// any order: "pitt bra" becomes "pitt"* "bra"*. None if nothing searchable was typed.
+fn prefix_match_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+pub struct ActorSearchHit {
+    pub actor_id: i64,
+    pub name: String,
+    pub popularity: Option<f64>,
+    pub credit_count: usize,
+}
+
+// Actors whose name matches every typed word as a prefix, most popular first, then
+// by credit count, since popularity is only known after `--enrich-actors`.
+pub fn search_actors(conn: &Connection, text: &str, limit: usize) -> Result<Vec<ActorSearchHit>> {
+    let Some(query) = prefix_match_query(text) else {
+        return Ok(Vec::new());
+    };
+    let mut stmt = conn.prepare(
+        "SELECT a.actor_id, a.name, a.popularity, a.credit_count
+         FROM actor_search s JOIN actors a ON a.actor_id = s.rowid
+         WHERE actor_search MATCH ?
+         ORDER BY COALESCE(a.popularity, -1) DESC, a.credit_count DESC, a.actor_id LIMIT ?",
+    )?;
+    let rows = stmt.query_map((query, limit as i64), |row| {
+        Ok(ActorSearchHit { actor_id: row.get(0)?, name: row.get(1)?, popularity: row.get(2)?, credit_count: row.get(3)? })
+    })?;
+    rows.collect()
+}
+
+pub struct MovieSearchHit {
+    pub movie_id: i64,
+    pub title: String,
+    pub release_date: Option<String>,
+    pub popularity: Option<f64>,
+    pub credit_count: usize,
+}
+
+// Movies whose title matches every typed word as a prefix, most popular first, then by cast size.
+pub fn search_movies(conn: &Connection, text: &str, limit: usize) -> Result<Vec<MovieSearchHit>> {
+    let Some(query) = prefix_match_query(text) else {
+        return Ok(Vec::new());
+    };
+    let mut stmt = conn.prepare(
+        "SELECT m.movie_id, m.title, m.release_date, m.popularity, m.credit_count
+         FROM movie_search s JOIN movies m ON m.movie_id = s.rowid
+         WHERE movie_search MATCH ?
+         ORDER BY COALESCE(m.popularity, -1) DESC, m.credit_count DESC, m.movie_id LIMIT ?",
+    )?;
+    let rows = stmt.query_map((query, limit as i64), |row| {
+        Ok(MovieSearchHit {
+            movie_id: row.get(0)?,
+            title: row.get(1)?,
+            release_date: row.get(2)?,
+            popularity: row.get(3)?,
+            credit_count: row.get(4)?,
+        })
+    })?;
+    rows.collect()
+}
+
+#[derive(Serialize)]
+pub struct QueryPlan {
+    pub name: &'static str,
+    pub sql: &'static str,
+    pub steps: Vec<String>, // One line per plan step, e.g. "SEARCH actors USING INDEX actors_by_name (name=?)"
+}
+
+// SQLite's EXPLAIN QUERY PLAN output for a statement. Parameters are bound to NULL,
+// which doesn't change the plan.
+pub fn explain_query_plan(conn: &Connection, sql: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {}", sql))?;
+    let nulls = std::iter::repeat_n(Null, stmt.parameter_count());
+    let rows = stmt.query_map(params_from_iter(nulls), |row| row.get(3))?;
+    rows.collect()
+}
+
+// How SQLite runs each hot lookup, to check that none of them scans a whole table.
+pub fn lookup_query_plans(conn: &Connection) -> Result<Vec<QueryPlan>> {
+    LOOKUP_QUERIES
+        .iter()
+        .map(|&(name, sql)| Ok(QueryPlan { name, sql, steps: explain_query_plan(conn, sql)? }))
+        .collect()
+}
+
+#[derive(Default)]
+pub struct ActorDetails<'a> {
+    pub profile_path: Option<&'a str>,
+    pub popularity: Option<f64>,
+    pub gender: Option<u8>, // TMDB's code: 0 not set, 1 female, 2 male, 3 non-binary
+    pub birthday: Option<&'a str>, // "YYYY-MM-DD"
+    pub deathday: Option<&'a str>,
+    pub place_of_birth: Option<&'a str>,
+}
+
+pub fn update_actor_details(conn: &Connection, tmdb_actor_id: u32, details: &ActorDetails) -> Result<()> {
+    conn.execute(
+        "UPDATE actors SET profile_path = ?, popularity = ?, gender = ?, birthday = ?, deathday = ?, place_of_birth = ?,
+            details_fetched_at = datetime('now')
+        WHERE tmdb_actor_id = ?",
+        (details.profile_path, details.popularity, details.gender, details.birthday, details.deathday, details.place_of_birth, tmdb_actor_id),
+    )?;
+    Ok(())
+}
+
+// Actors the enrichment pass hasn't looked up yet, oldest first.
+pub fn get_tmdb_actor_ids_without_details(conn: &Connection, limit: Option<usize>) -> Result<Vec<u32>> {
+    let mut stmt = conn.prepare("SELECT tmdb_actor_id FROM actors WHERE details_fetched_at IS NULL ORDER BY actor_id LIMIT ?")?;
+    let rows = stmt.query_map([limit.map_or(-1, |limit| limit as i64)], |row| row.get(0))?;
+    rows.collect()
+}
+
+#[derive(Clone)]
+pub struct ActorMetadata {
+    pub actor_id: i64,
+    pub tmdb_actor_id: u32,
+    pub name: String,
+    pub known_for_department: Option<String>,
+    pub profile_path: Option<String>,
+    pub popularity: Option<f64>,
+    pub gender: Option<u8>,
+    pub birthday: Option<String>,
+    pub deathday: Option<String>,
+    pub place_of_birth: Option<String>,
+}
+
+// Name and whatever details enrichment stored for each actor found.
+pub fn get_actor_metadata_by_ids(conn: &Connection, actor_ids: &HashSet<i64>) -> Result<HashMap<i64, ActorMetadata>> {
+    let mut stmt = conn.prepare(
+        "SELECT name, known_for_department, profile_path, popularity, gender, birthday, deathday, place_of_birth, tmdb_actor_id
+         FROM actors WHERE actor_id = ?",
+    )?;
+    let mut actors = HashMap::new();
+    for &actor_id in actor_ids {
+        let mut rows = stmt.query([actor_id])?;
+        if let Some(row) = rows.next()? {
+            actors.insert(actor_id, ActorMetadata {
+                actor_id,
+                tmdb_actor_id: row.get(8)?,
+                name: row.get(0)?,
+                known_for_department: row.get(1)?,
+                profile_path: row.get(2)?,
+                popularity: row.get(3)?,
+                gender: row.get(4)?,
+                birthday: row.get(5)?,
+                deathday: row.get(6)?,
+                place_of_birth: row.get(7)?,
+            });
+        }
+    }
+    Ok(actors)
+}
+
+pub fn clear_actor_centrality(conn: &Connection) -> Result<()> {
+    conn.execute("DELETE FROM actor_centrality", ())?;
+    Ok(())
+}
+
+pub fn insert_actor_centrality(conn: &Connection, actor_id: i64, degree: usize, closeness: f64) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO actor_centrality (actor_id, degree, closeness) VALUES (?, ?, ?)",
+        (actor_id, degree, closeness),
+    )?;
+    Ok(())
+}
+
+pub struct CentralActor {
+    pub actor_id: i64,
+    pub name: String,
+    pub degree: usize,
+    pub closeness: f64,
+}
+
+// Best-connected actors from the last centrality run, by closeness or by degree.
+pub fn get_central_actors(conn: &Connection, by_degree: bool, limit: usize) -> Result<Vec<CentralActor>> {
+    let order_by = if by_degree { "c.degree DESC, c.closeness DESC" } else { "c.closeness DESC, c.degree DESC" };
+    let mut stmt = conn.prepare(&format!(
+        "SELECT c.actor_id, a.name, c.degree, c.closeness FROM actor_centrality c
+         JOIN actors a ON a.actor_id = c.actor_id
+         ORDER BY {}, c.actor_id LIMIT ?",
+        order_by
+    ))?;
+    let rows = stmt.query_map([limit as i64], |row| {
+        Ok(CentralActor { actor_id: row.get(0)?, name: row.get(1)?, degree: row.get(2)?, closeness: row.get(3)? })
+    })?;
+    rows.collect()
+}
+
+pub fn clear_actor_components(conn: &Connection) -> Result<()> {
+    conn.execute("DELETE FROM actor_components", ())?;
+    Ok(())
+}
+
+pub fn insert_actor_component(conn: &Connection, actor_id: i64, component_id: u32, component_size: usize) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO actor_components (actor_id, component_id, component_size) VALUES (?, ?, ?)",
+        (actor_id, component_id, component_size),
+    )?;
+    Ok(())
+}
+
+pub fn clear_actor_communities(conn: &Connection) -> Result<()> {
+    conn.execute("DELETE FROM actor_communities", ())?;
+    Ok(())
+}
+
+pub fn insert_actor_community(conn: &Connection, actor_id: i64, community_id: u32, community_size: usize, internal_degree: usize) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO actor_communities (actor_id, community_id, community_size, internal_degree) VALUES (?, ?, ?, ?)",
+        (actor_id, community_id, community_size, internal_degree),
+    )?;
+    Ok(())
+}
+
+pub struct ActorCommunity {
+    pub community_id: i64,
+    pub community_size: usize,
+}
+
+pub fn get_actor_community(conn: &Connection, actor_id: i64) -> Result<Option<ActorCommunity>> {
+    let mut stmt = conn.prepare("SELECT community_id, community_size FROM actor_communities WHERE actor_id = ?")?;
+    let mut rows = stmt.query([actor_id])?;
+
+    if let Some(row) = rows.next()? {
+        Ok(Some(ActorCommunity { community_id: row.get(0)?, community_size: row.get(1)? }))
+    } else {
+        Ok(None) // Actor not labeled (no credits, or communities not computed yet)
+    }
+}
+
+pub struct CommunityHub {
+    pub actor_id: i64,
+    pub name: String,
+    pub internal_degree: usize,
+}
+
+// Members of a community with the most co-stars inside it.
+pub fn get_community_hubs(conn: &Connection, community_id: i64, limit: usize) -> Result<Vec<CommunityHub>> {
+    let mut stmt = conn.prepare(
+        "SELECT c.actor_id, a.name, c.internal_degree FROM actor_communities c
+         JOIN actors a ON a.actor_id = c.actor_id
+         WHERE c.community_id = ?
+         ORDER BY c.internal_degree DESC, c.actor_id LIMIT ?",
+    )?;
+    let rows = stmt.query_map((community_id, limit as i64), |row| {
+        Ok(CommunityHub { actor_id: row.get(0)?, name: row.get(1)?, internal_degree: row.get(2)? })
+    })?;
+    rows.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::ActorGraph;
+
+    // A database as the first release created it, before migrations were tracked
+    fn old_schema_connection() -> Result<Connection> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            "CREATE TABLE actors (
+                actor_id        INTEGER PRIMARY KEY AUTOINCREMENT,
+                tmdb_actor_id   INTEGER UNIQUE NOT NULL,
+                name            TEXT NOT NULL,
+                known_for_department TEXT
+            );
+            CREATE TABLE movies (
+                movie_id        INTEGER PRIMARY KEY AUTOINCREMENT,
+                tmdb_movie_id   INTEGER UNIQUE NOT NULL,
+                title           TEXT NOT NULL
+            );
+            CREATE TABLE movie_actors (
+                movie_actor_id  INTEGER PRIMARY KEY AUTOINCREMENT,
+                movie_id        INTEGER NOT NULL,
+                actor_id        INTEGER NOT NULL
+            );
+            INSERT INTO actors (tmdb_actor_id, name, known_for_department) VALUES (287, 'Brad Pitt', 'Acting'), (819, 'Edward Norton', 'Acting');
+            INSERT INTO movies (tmdb_movie_id, title) VALUES (550, 'Fight Club');
+            INSERT INTO movie_actors (movie_id, actor_id) VALUES (1, 1), (1, 2), (1, 1);",
+        )?;
+        Ok(conn)
+    }
+
+    #[test]
+    fn test_setup_database_upgrades_old_schema() -> Result<()> {
+        let conn = old_schema_connection()?;
+        assert_eq!(schema_version(&conn)?, 0);
+        setup_database(&conn)?;
+        assert_eq!(schema_version(&conn)?, SCHEMA_VERSION);
+
+        // Old rows survive and the new columns and tables are usable
+        assert_eq!(get_actor_id_by_name(&conn, "Edward Norton")?, Some(2));
+        assert_eq!(get_actors_by_normalized_name(&conn, "edward norton")?, vec![(2, "Edward Norton".to_string())]);
+        insert_movie(&conn, &NewMovie { tmdb_movie_id: 807, title: "Se7en", release_date: Some("1995-09-22"), ..Default::default() })?;
+        insert_movie_actor_link(&conn, 2, 1, Some(0), Some("Detective David Mills"))?;
+        insert_genre(&conn, 18, "Drama")?;
+        insert_movie_genre(&conn, 1, 18)?;
+        insert_actor_centrality(&conn, 1, 1, 1.0)?;
+        let graph = ActorGraph::load(&conn)?;
+        assert_eq!(graph.shared_movies(1, 2), vec![1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lookups_use_indexes() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        setup_database(&conn)?;
+        for plan in lookup_query_plans(&conn)? {
+            assert!(!plan.steps.is_empty());
+            for step in &plan.steps {
+                // Known-for movies are ranked by popularity, so one actor's credits get sorted
+                if plan.name == "known_for_movies" && step == "USE TEMP B-TREE FOR ORDER BY" {
+                    continue;
+                }
+                assert!(step.starts_with("SEARCH"), "{} scans: {}", plan.name, step);
+                assert!(!step.contains("TEMP B-TREE"), "{} sorts: {}", plan.name, step);
+            }
+        }
+        let credit_steps = explain_query_plan(&conn, MOVIE_IDS_FOR_ACTOR)?;
+        assert!(credit_steps[0].contains("COVERING INDEX movie_actors_by_actor"));
+        let fuzzy_steps = explain_query_plan(&conn, ACTORS_NEAR_NORMALIZED_NAME)?;
+        assert!(fuzzy_steps[0].contains("INDEX actors_by_initial_and_length"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_movie_metadata() -> Result<()> {
+        let conn = crate::test_support::fixture_connection()?;
+        let movies = get_movie_metadata_by_ids(&conn, &HashSet::from([2, 999]))?;
+        assert_eq!(movies.len(), 1);
+        assert_eq!(movies[&2].title, "Se7en");
+        assert_eq!(movies[&2].genres, vec!["Crime", "Thriller"]);
+        assert_eq!(movies[&2].release_date.as_deref(), Some("1995-09-22"));
+        assert_eq!(movies[&2].runtime, None);
+
+        // Populating again fills in the details without renumbering the movie
+        insert_movie(&conn, &NewMovie {
+            tmdb_movie_id: 550,
+            title: "Fight Club",
+            release_date: Some("1999-10-15"),
+            popularity: Some(70.0),
+            vote_count: Some(30000),
+            runtime: Some(139),
+            poster_path: Some("/pB8BM7pdSp6B6Ih7QZ4DrQ3PmJK.jpg"),
+        })?;
+        let fight_club = &get_movie_metadata_by_ids(&conn, &HashSet::from([1]))?[&1];
+        assert_eq!((fight_club.runtime, fight_club.vote_count, fight_club.popularity), (Some(139), Some(30000), Some(70.0)));
+        assert_eq!(fight_club.poster_path.as_deref(), Some("/pB8BM7pdSp6B6Ih7QZ4DrQ3PmJK.jpg"));
+        assert_eq!(get_movie_count(&conn)?, 8);
+        Ok(())
+    }
+
+    #[test]
+    fn test_actor_details() -> Result<()> {
+        let conn = crate::test_support::fixture_connection()?;
+        assert_eq!(get_tmdb_actor_ids_without_details(&conn, None)?.len(), 13);
+        assert_eq!(get_tmdb_actor_ids_without_details(&conn, Some(2))?, vec![819, 287]);
+
+        update_actor_details(&conn, 287, &ActorDetails {
+            profile_path: Some("/kU3B75TyRiCgE270EyZnHjfivoq.jpg"),
+            popularity: Some(20.5),
+            gender: Some(2),
+            birthday: Some("1963-12-18"),
+            place_of_birth: Some("Shawnee, Oklahoma, USA"),
+            ..Default::default()
+        })?;
+        // Looked-up actors aren't fetched again, even when TMDB had nothing on them
+        update_actor_details(&conn, 819, &ActorDetails::default())?;
+        assert_eq!(get_tmdb_actor_ids_without_details(&conn, Some(1))?, vec![1283]);
+
+        let actors = get_actor_metadata_by_ids(&conn, &HashSet::from([1, 2]))?;
+        assert_eq!(actors[&2].name, "Brad Pitt");
+        assert_eq!(actors[&2].birthday.as_deref(), Some("1963-12-18"));
+        assert_eq!((actors[&2].gender, actors[&2].deathday.as_deref()), (Some(2), None));
+        assert_eq!(actors[&1].profile_path, None);
+        assert_eq!(actors[&1].known_for_department.as_deref(), Some("Acting"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_known_for_movies() -> Result<()> {
+        let conn = crate::test_support::fixture_connection()?;
+        // Norton's movies by popularity
+        let titles: Vec<String> = get_known_for_movies(&conn, 1, 3)?.into_iter().map(|movie| movie.title).collect();
+        assert_eq!(titles, vec!["Fight Club", "American History X", "Primal Fear"]);
+        assert_eq!(get_actor_id_by_tmdb_id(&conn, 287)?, Some(2));
+        assert_eq!(get_actor_id_by_tmdb_id(&conn, 1)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_search() -> Result<()> {
+        let conn = crate::test_support::fixture_connection()?;
+        let actor_ids = |text: &str| search_actors(&conn, text, 10).map(|hits| hits.into_iter().map(|hit| hit.actor_id).collect::<Vec<i64>>());
+        assert_eq!(actor_ids("bra")?, vec![2]);
+        assert_eq!(actor_ids("pitt b")?, vec![2]);
+        // Norton has more credits than Furlong
+        assert_eq!(actor_ids("edw")?, vec![1, 9]);
+        assert_eq!(actor_ids("\"*")?, Vec::<i64>::new());
+
+        // Kept in step with the actors table, ignoring accents
+        insert_actor(&conn, 955, "Penélope Cruz", "Acting")?;
+        assert_eq!(actor_ids("penel")?, vec![14]);
+        conn.execute("UPDATE actors SET name = 'Brad Pitt Jr' WHERE actor_id = 2", ())?;
+        assert_eq!(actor_ids("pitt jr")?, vec![2]);
+        conn.execute("DELETE FROM actors WHERE actor_id = 14", ())?;
+        assert_eq!(actor_ids("penel")?, Vec::<i64>::new());
+
+        // Movies by popularity: Shawshank, The Usual Suspects, The Incredible Hulk
+        let titles: Vec<String> = search_movies(&conn, "the", 10)?.into_iter().map(|hit| hit.title).collect();
+        assert_eq!(titles, vec!["The Shawshank Redemption", "The Usual Suspects", "The Incredible Hulk"]);
+        assert_eq!(search_movies(&conn, "the", 1)?.len(), 1);
+        assert_eq!(search_movies(&conn, "fight", 10)?[0].credit_count, 4);
+
+        // Stored credit counts follow movie_actors, and re-ingesting a credit doesn't count it twice
+        let furlong_credits = |conn: &Connection| search_actors(conn, "furlong", 1).map(|hits| hits[0].credit_count);
+        insert_movie_actor_link(&conn, 2, 9, Some(3), None)?;
+        insert_movie_actor_link(&conn, 2, 9, Some(3), Some("Extra"))?;
+        assert_eq!(furlong_credits(&conn)?, 2);
+        assert_eq!(search_movies(&conn, "se7en", 1)?[0].credit_count, 4);
+        assert_eq!(actor_ids("edw")?, vec![1, 9]);
+        conn.execute("DELETE FROM movie_actors WHERE movie_id = 2 AND actor_id = 9", ())?;
+        assert_eq!(furlong_credits(&conn)?, 1);
+        Ok(())
+    }
+
+    fn credit_rows(conn: &Connection, movie_id: i64, actor_id: i64) -> Result<Vec<Option<u32>>> {
+        let mut stmt = conn.prepare("SELECT cast_order FROM movie_actors WHERE movie_id = ? AND actor_id = ?")?;
+        let rows = stmt.query_map([movie_id, actor_id], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    #[test]
+    fn test_duplicate_credits() -> Result<()> {
+        let conn = old_schema_connection()?;
+        migrate_v1_base_schema(&conn)?;
+        migrate_v2_movie_metadata(&conn)?;
+        conn.execute("UPDATE movie_actors SET cast_order = 3 WHERE movie_actor_id = 3", ())?;
+        conn.pragma_update(None, "user_version", 3)?;
+
+        // The duplicate Fight Club credit collapses, keeping the billing only it had
+        setup_database(&conn)?;
+        assert_eq!(credit_rows(&conn, 1, 1)?, vec![Some(3)]);
+        assert_eq!(credit_rows(&conn, 1, 2)?, vec![None]);
+
+        // Re-inserting keeps a single row with the best billing seen
+        insert_movie_actor_link(&conn, 1, 1, Some(5), None)?;
+        insert_movie_actor_link(&conn, 1, 1, Some(0), None)?;
+        insert_movie_actor_link(&conn, 1, 1, None, None)?;
+        insert_movie_actor_link(&conn, 1, 2, Some(1), None)?;
+        assert_eq!(credit_rows(&conn, 1, 1)?, vec![Some(0)]);
+        assert_eq!(credit_rows(&conn, 1, 2)?, vec![Some(1)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_character_names() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        setup_database(&conn)?;
+        insert_actor(&conn, 819, "Edward Norton", "Acting")?;
+        insert_movie(&conn, &NewMovie { tmdb_movie_id: 550, title: "Fight Club", ..Default::default() })?;
+        insert_movie_actor_link(&conn, 1, 1, Some(0), None)?;
+        assert!(get_character_names(&conn, 1, 1)?.is_empty());
+        insert_movie_actor_link(&conn, 1, 1, Some(0), Some("Tyler Durden"))?;
+        assert_eq!(get_character_names(&conn, 1, 1)?, vec!["Tyler Durden"]);
+
+        // A second role in the same movie is kept once, however often it's ingested
+        insert_movie_actor_link(&conn, 1, 1, Some(0), Some("Narrator's Double"))?;
+        insert_movie_actor_link(&conn, 1, 1, Some(0), Some("Narrator's Double"))?;
+        insert_movie_actor_link(&conn, 1, 1, Some(0), Some("Tyler Durden"))?;
+        assert_eq!(get_character_names(&conn, 1, 1)?, vec!["Tyler Durden", "Narrator's Double"]);
+        assert!(get_character_names(&conn, 2, 1)?.is_empty());
+
+        // Roles are compared whole, even when TMDB's own role names contain " / "
+        insert_movie_actor_link(&conn, 1, 1, Some(0), Some("Tyler"))?;
+        insert_movie_actor_link(&conn, 1, 1, Some(0), Some("Himself / Host"))?;
+        insert_movie_actor_link(&conn, 1, 1, Some(0), Some("Himself"))?;
+        insert_movie_actor_link(&conn, 1, 1, Some(0), Some("Host"))?;
+        insert_movie_actor_link(&conn, 1, 1, Some(0), Some("Himself / Host"))?;
+        assert_eq!(
+            get_character_names(&conn, 1, 1)?,
+            vec!["Tyler Durden", "Narrator's Double", "Tyler", "Himself / Host", "Himself", "Host"]
+        );
+
+        // Roles merged into movie_actors before credit_roles existed carry over as one role
+        conn.execute("DELETE FROM credit_roles", ())?;
+        conn.execute("UPDATE movie_actors SET character_name = 'Tyler Durden / Narrator''s Double' WHERE movie_id = 1", ())?;
+        migrate_v13_credit_roles(&conn)?;
+        assert_eq!(get_character_names(&conn, 1, 1)?, vec!["Tyler Durden / Narrator's Double"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_actor_name_with_character() -> Result<()> {
+        let conn = crate::test_support::fixture_connection()?;
+        // Brad Pitt in Fight Club; Tim Robbins' Shawshank role isn't in the fixture
+        assert_eq!(actor_name_with_character(&conn, "Brad Pitt", 1, 2)?, "Brad Pitt (Tyler Durden)");
+        assert_eq!(actor_name_with_character(&conn, "Tim Robbins", 5, 10)?, "Tim Robbins");
+        insert_movie_actor_link(&conn, 1, 2, Some(1), Some("Himself / Host"))?;
+        assert_eq!(actor_name_with_character(&conn, "Brad Pitt", 1, 2)?, "Brad Pitt (Tyler Durden / Himself / Host)");
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_is_idempotent() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        assert_eq!(migrate(&conn)?, 0);
+        assert_eq!(migrate(&conn)?, SCHEMA_VERSION);
+        assert_eq!(schema_version(&conn)?, SCHEMA_VERSION);
+
+        // Pre-migration databases may already have a later column
+        let conn = old_schema_connection()?;
+        conn.execute("ALTER TABLE movie_actors ADD COLUMN cast_order INTEGER", ())?;
+        setup_database(&conn)?;
+        assert_eq!(schema_version(&conn)?, SCHEMA_VERSION);
+        Ok(())
+    }
+}
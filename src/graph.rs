@@ -0,0 +1,262 @@
+use rusqlite::{Connection, Result};
+use std::collections::HashMap;
+
+// Billing position stored for credits whose cast order is unknown.
+pub const UNKNOWN_ORDER: u32 = u32::MAX;
+
+// One row of `movie_actors`.
+#[derive(Debug, Clone, Copy)]
+pub struct Credit {
+    pub movie_id: i64,
+    pub actor_id: i64,
+    pub cast_order: Option<u32>,
+}
+
+// Per-movie attributes searches can filter on.
+#[derive(Debug, Clone, Default)]
+pub struct MovieInfo {
+    pub release_year: Option<i32>,
+    pub genre_ids: Vec<i64>,
+    pub popularity: Option<f64>, // TMDB popularity score
+}
+
+// Bipartite actor/movie graph built once from `movie_actors`.
+// Nodes are addressed by dense indices; the neighbours of node `i` live in
+// `targets[offsets[i]..offsets[i + 1]]` (CSR layout), so expanding a node
+// during a search is a slice lookup instead of a SQL query. Each target has
+// the credit's cast order stored at the same position in a parallel array.
+pub struct ActorGraph {
+    actor_ids: Vec<i64>,
+    movie_ids: Vec<i64>,
+    actor_index: HashMap<i64, u32>,
+    movie_index: HashMap<i64, u32>,
+    actor_offsets: Vec<u32>,
+    actor_movies: Vec<u32>,
+    actor_movie_orders: Vec<u32>,
+    movie_offsets: Vec<u32>,
+    movie_actors: Vec<u32>,
+    movie_actor_orders: Vec<u32>,
+    movie_info: Vec<MovieInfo>,
+}
+
+impl ActorGraph {
+    pub fn load(conn: &Connection) -> Result<ActorGraph> {
+        let mut stmt = conn.prepare("SELECT movie_id, actor_id, cast_order FROM movie_actors")?;
+        let credits = stmt
+            .query_map([], |row| Ok(Credit { movie_id: row.get(0)?, actor_id: row.get(1)?, cast_order: row.get(2)? }))?
+            .collect::<Result<Vec<Credit>>>()?;
+        let mut graph = ActorGraph::from_credits(credits);
+
+        let mut stmt = conn.prepare("SELECT movie_id, release_date, popularity FROM movies")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let release_date: Option<String> = row.get(1)?;
+            if let Some(movie) = graph.movie_index(row.get(0)?) {
+                graph.movie_info[movie].release_year = release_date.as_deref().and_then(parse_release_year);
+                graph.movie_info[movie].popularity = row.get(2)?;
+            }
+        }
+
+        let mut stmt = conn.prepare("SELECT movie_id, genre_id FROM movie_genres")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            if let Some(movie) = graph.movie_index(row.get(0)?) {
+                graph.movie_info[movie].genre_ids.push(row.get(1)?);
+            }
+        }
+
+        Ok(graph)
+    }
+
+    // Builds the graph from credits alone; movie info starts out empty.
+    // Duplicate credits are collapsed, keeping the best billing position.
+    pub fn from_credits(mut credits: Vec<Credit>) -> ActorGraph {
+        credits.sort_unstable_by_key(|credit| (credit.movie_id, credit.actor_id, credit.cast_order.unwrap_or(UNKNOWN_ORDER)));
+        credits.dedup_by_key(|credit| (credit.movie_id, credit.actor_id));
+
+        let mut movie_ids: Vec<i64> = credits.iter().map(|credit| credit.movie_id).collect();
+        movie_ids.dedup(); // already sorted by movie_id
+        let mut actor_ids: Vec<i64> = credits.iter().map(|credit| credit.actor_id).collect();
+        actor_ids.sort_unstable();
+        actor_ids.dedup();
+
+        let movie_index: HashMap<i64, u32> = movie_ids.iter().enumerate().map(|(i, &id)| (id, i as u32)).collect();
+        let actor_index: HashMap<i64, u32> = actor_ids.iter().enumerate().map(|(i, &id)| (id, i as u32)).collect();
+
+        let edges: Vec<(u32, u32, u32)> = credits
+            .iter()
+            .map(|credit| (movie_index[&credit.movie_id], actor_index[&credit.actor_id], credit.cast_order.unwrap_or(UNKNOWN_ORDER)))
+            .collect();
+
+        let (movie_offsets, movie_actors, movie_actor_orders) = build_csr(movie_ids.len(), edges.iter().copied());
+        let (actor_offsets, actor_movies, actor_movie_orders) =
+            build_csr(actor_ids.len(), edges.iter().map(|&(movie, actor, order)| (actor, movie, order)));
+
+        let movie_info = vec![MovieInfo::default(); movie_ids.len()];
+        ActorGraph {
+            actor_ids,
+            movie_ids,
+            actor_index,
+            movie_index,
+            actor_offsets,
+            actor_movies,
+            actor_movie_orders,
+            movie_offsets,
+            movie_actors,
+            movie_actor_orders,
+            movie_info,
+        }
+    }
+
+    pub fn actor_count(&self) -> usize {
+        self.actor_ids.len()
+    }
+
+    pub fn movie_count(&self) -> usize {
+        self.movie_ids.len()
+    }
+
+    pub fn actor_index(&self, actor_id: i64) -> Option<usize> {
+        self.actor_index.get(&actor_id).map(|&i| i as usize)
+    }
+
+    pub fn movie_index(&self, movie_id: i64) -> Option<usize> {
+        self.movie_index.get(&movie_id).map(|&i| i as usize)
+    }
+
+    pub fn actor_id(&self, actor: usize) -> i64 {
+        self.actor_ids[actor]
+    }
+
+    pub fn movie_id(&self, movie: usize) -> i64 {
+        self.movie_ids[movie]
+    }
+
+    // Movie indices for an actor index, in ascending movie_id order.
+    pub fn movies_of(&self, actor: usize) -> &[u32] {
+        &self.actor_movies[self.actor_offsets[actor] as usize..self.actor_offsets[actor + 1] as usize]
+    }
+
+    // Cast orders matching `movies_of(actor)` position by position.
+    pub fn movie_orders_of(&self, actor: usize) -> &[u32] {
+        &self.actor_movie_orders[self.actor_offsets[actor] as usize..self.actor_offsets[actor + 1] as usize]
+    }
+
+    // Actor indices for a movie index, in ascending actor_id order.
+    pub fn cast_of(&self, movie: usize) -> &[u32] {
+        &self.movie_actors[self.movie_offsets[movie] as usize..self.movie_offsets[movie + 1] as usize]
+    }
+
+    // Cast orders matching `cast_of(movie)` position by position.
+    pub fn cast_orders_of(&self, movie: usize) -> &[u32] {
+        &self.movie_actor_orders[self.movie_offsets[movie] as usize..self.movie_offsets[movie + 1] as usize]
+    }
+
+    pub fn movie_info(&self, movie: usize) -> &MovieInfo {
+        &self.movie_info[movie]
+    }
+
+    // Whether the actor has a credit in the movie.
+    pub fn appears_in(&self, actor_id: i64, movie_id: i64) -> bool {
+        match (self.actor_index(actor_id), self.movie_index(movie_id)) {
+            (Some(actor), Some(movie)) => self.movies_of(actor).binary_search(&(movie as u32)).is_ok(),
+            _ => false,
+        }
+    }
+
+    // Movie IDs both actors appear in.
+    pub fn shared_movies(&self, first_actor_id: i64, second_actor_id: i64) -> Vec<i64> {
+        let (Some(first), Some(second)) = (self.actor_index(first_actor_id), self.actor_index(second_actor_id)) else {
+            return Vec::new();
+        };
+        let (a, b) = (self.movies_of(first), self.movies_of(second));
+        let (mut i, mut j) = (0, 0);
+        let mut shared = Vec::new();
+        while i < a.len() && j < b.len() {
+            match a[i].cmp(&b[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    shared.push(self.movie_id(a[i] as usize));
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        shared
+    }
+}
+
+// Release dates are stored as TMDB's "YYYY-MM-DD"; empty strings mean unknown.
+pub fn parse_release_year(release_date: &str) -> Option<i32> {
+    release_date.get(..4)?.parse().ok()
+}
+
+// Counting-sort the (source, target, order) edges into offset/target/order arrays.
+// Targets keep the relative order of the input iterator.
+fn build_csr(node_count: usize, edges: impl Iterator<Item = (u32, u32, u32)> + Clone) -> (Vec<u32>, Vec<u32>, Vec<u32>) {
+    let mut offsets = vec![0u32; node_count + 1];
+    for (source, _, _) in edges.clone() {
+        offsets[source as usize + 1] += 1;
+    }
+    for i in 0..node_count {
+        offsets[i + 1] += offsets[i];
+    }
+    let mut cursor = offsets.clone();
+    let mut targets = vec![0u32; offsets[node_count] as usize];
+    let mut orders = vec![UNKNOWN_ORDER; offsets[node_count] as usize];
+    for (source, target, order) in edges {
+        let position = cursor[source as usize] as usize;
+        targets[position] = target;
+        orders[position] = order;
+        cursor[source as usize] += 1;
+    }
+    (offsets, targets, orders)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[test]
+    fn test_cast_of_movie() -> Result<()> {
+        let conn = test_support::fixture_connection()?;
+        let graph = ActorGraph::load(&conn)?;
+        // Movie 1 (Fight Club) has actors
+        let movie = graph.movie_index(1).unwrap();
+        assert!(!graph.cast_of(movie).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_movies_of_actor() -> Result<()> {
+        let conn = test_support::fixture_connection()?;
+        let graph = ActorGraph::load(&conn)?;
+        // Actor 2 (Brad Pitt) has movies
+        let actor = graph.actor_index(2).unwrap();
+        assert!(!graph.movies_of(actor).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_duplicate_credits_are_collapsed() {
+        let credit = |movie_id, actor_id, cast_order| Credit { movie_id, actor_id, cast_order };
+        let graph = ActorGraph::from_credits(vec![credit(1, 2, Some(4)), credit(1, 2, Some(1)), credit(1, 3, None)]);
+        let movie = graph.movie_index(1).unwrap();
+        assert_eq!(graph.cast_of(movie).len(), 2);
+        assert_eq!(graph.cast_orders_of(movie), &[1, UNKNOWN_ORDER]);
+        assert_eq!(graph.shared_movies(2, 3), vec![1]);
+    }
+
+    #[test]
+    fn test_movie_info_is_loaded() -> Result<()> {
+        let conn = test_support::fixture_connection()?;
+        let graph = ActorGraph::load(&conn)?;
+        // Movie 1 (Fight Club, 1999, Drama)
+        let info = graph.movie_info(graph.movie_index(1).unwrap());
+        assert_eq!(info.release_year, Some(1999));
+        assert_eq!(info.genre_ids, vec![18]);
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use actor_link::centrality::{compute_centrality, save_centrality};
+use actor_link::communities::{save_communities, Communities};
+use actor_link::components::{save_components, Components};
+use actor_link::db;
+use actor_link::graph::ActorGraph;
+use actor_link::landmarks::{LandmarkIndex, DEFAULT_INDEX_PATH, DEFAULT_LANDMARK_COUNT};
+use actor_link::link_finder::distance_distribution;
+use actor_link::name_resolver::{resolve_actor_name, DEFAULT_CANDIDATE_COUNT};
+use std::env;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: graph_stats distribution <actor name>
+       graph_stats centrality [--samples <count>] [--seed <seed>]
+       graph_stats components
+       graph_stats landmarks [--count <landmarks>]
+       graph_stats communities [--seed <seed>]";
+
+// Prints the distance table for one center actor.
+fn print_distribution(conn: &rusqlite::Connection, graph: &ActorGraph, actor_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let candidates = resolve_actor_name(conn, actor_name, DEFAULT_CANDIDATE_COUNT)?;
+    let actor_id = match candidates.first() {
+        Some(best) if best.distance == 0 => {
+            if candidates.iter().filter(|candidate| candidate.distance == 0).count() > 1 {
+                eprintln!("'{}' matches several actors; using actor_id {}, the one with most credits.", actor_name, best.actor_id);
+            }
+            best.actor_id
+        }
+        _ => {
+            let names: Vec<String> = candidates.iter().map(|candidate| format!("'{}'", candidate.name)).collect();
+            let hint = if names.is_empty() { String::new() } else { format!(" Did you mean {}?", names.join(", ")) };
+            return Err(format!("Actor '{}' not found in database.{}", actor_name, hint).into());
+        }
+    };
+    let distribution =
+        distance_distribution(graph, actor_id).ok_or_else(|| format!("Actor '{}' has no credits to link through.", actor_name))?;
+
+    println!("Distances from {}:", actor_name);
+    println!("{:>8}  {:>10}", "Distance", "Actors");
+    for (distance, count) in distribution.counts.iter().enumerate().skip(1) {
+        println!("{:>8}  {:>10}", distance, count);
+    }
+    println!("{:>8}  {:>10}", "None", distribution.unreachable);
+    println!("Reachable actors: {}", distribution.reachable);
+    println!("Average distance: {:.3}", distribution.average_distance);
+    Ok(())
+}
+
+// Recomputes degree and closeness for every actor and stores them in actor_centrality.
+fn run_centrality(conn: &rusqlite::Connection, graph: &ActorGraph, options: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut samples = None;
+    let mut seed = 0;
+    for pair in options.chunks(2) {
+        match pair {
+            [flag, value] if flag == "--samples" => samples = Some(value.parse()?),
+            [flag, value] if flag == "--seed" => seed = value.parse()?,
+            _ => return Err(USAGE.into()),
+        }
+    }
+
+    let centrality = compute_centrality(graph, samples, seed);
+    save_centrality(conn, &centrality)?;
+    println!("Stored centrality for {} actors.", centrality.len());
+    Ok(())
+}
+
+// Labels every actor with its connected component and stores them in actor_components.
+fn run_components(conn: &rusqlite::Connection, graph: &ActorGraph) -> Result<(), Box<dyn std::error::Error>> {
+    let components = Components::compute(graph);
+    save_components(conn, graph, &components)?;
+    println!("Stored {} components; the largest has {} of {} actors.", components.count(), components.largest_size(), graph.actor_count());
+    Ok(())
+}
+
+// Rebuilds the landmark distance index the server uses for fast searches and estimates.
+fn run_landmarks(graph: &ActorGraph, options: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let landmark_count = match options {
+        [] => DEFAULT_LANDMARK_COUNT,
+        [flag, value] if flag == "--count" => value.parse()?,
+        _ => return Err(USAGE.into()),
+    };
+    let index = LandmarkIndex::build(graph, landmark_count);
+    index.save(DEFAULT_INDEX_PATH)?;
+    println!("Stored {} landmarks in {}.", index.landmark_ids().len(), DEFAULT_INDEX_PATH);
+    Ok(())
+}
+
+// Clusters actors by label propagation and stores them in actor_communities.
+fn run_communities(conn: &rusqlite::Connection, graph: &ActorGraph, options: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let seed = match options {
+        [] => 0,
+        [flag, value] if flag == "--seed" => value.parse()?,
+        _ => return Err(USAGE.into()),
+    };
+    let communities = Communities::detect(graph, seed);
+    save_communities(conn, graph, &communities)?;
+    println!("Stored {} communities.", communities.count());
+    Ok(())
+}
+
+// Offline statistics over actor_link.db, for stats that are too slow for a request.
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some(command) = args.first() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+
+    let result = db::establish_connection().map_err(Box::from).and_then(|conn| {
+        db::setup_database(&conn)?; // Migrates older databases to have the tables the batch jobs write to
+        let graph = ActorGraph::load(&conn)?;
+        match (command.as_str(), &args[1..]) {
+            ("distribution", [actor_name]) => print_distribution(&conn, &graph, actor_name),
+            ("centrality", options) => run_centrality(&conn, &graph, options),
+            ("components", []) => run_components(&conn, &graph),
+            ("landmarks", options) => run_landmarks(&graph, options),
+            ("communities", options) => run_communities(&conn, &graph, options),
+            _ => Err(USAGE.into()),
+        }
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
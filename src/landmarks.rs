@@ -0,0 +1,247 @@
+use crate::graph::ActorGraph;
+use crate::link_finder::{actor_distances, UNREACHABLE};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+// Written next to actor_link.db and rebuilt whenever the database is repopulated.
+pub const DEFAULT_INDEX_PATH: &str = "actor_link.landmarks";
+pub const DEFAULT_LANDMARK_COUNT: usize = 16;
+
+const MAGIC: &[u8; 8] = b"ALTIDX02";
+// Stored distance for actors a landmark can't reach. Real distances stay far below it.
+const UNREACHABLE_BYTE: u8 = u8::MAX;
+
+// Precomputed hop distances from a few landmark actors to every actor. By the
+// triangle inequality |d(L, a) - d(L, b)| <= d(a, b) <= d(a, L) + d(L, b) for every
+// landmark L, which bounds any pair's distance without a search (ALT).
+pub struct LandmarkIndex {
+    actor_ids: Vec<i64>, // The graph's actor IDs in index order, to detect a stale index
+    credits_checksum: u64, // credits_checksum() of the graph, to detect changed credits between the same actors
+    landmarks: Vec<i64>,
+    distances: Vec<u8>, // distances[landmark * actor_count + actor]
+}
+
+// Bounds on an actor pair's degrees of separation.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SeparationEstimate {
+    pub lower_bound: u32,
+    pub upper_bound: Option<u32>, // None when no landmark reaches both actors
+}
+
+impl LandmarkIndex {
+    // Picks the best-connected actor first, then repeatedly the actor farthest from
+    // every landmark so far, so the landmarks end up spread around the graph's edge.
+    pub fn build(graph: &ActorGraph, landmark_count: usize) -> LandmarkIndex {
+        let actor_count = graph.actor_count();
+        let mut landmarks = Vec::new();
+        let mut distances = Vec::new();
+        let mut nearest_landmark = vec![UNREACHABLE; actor_count];
+
+        let mut next = (0..actor_count).max_by_key(|&actor| (graph.movies_of(actor).len(), std::cmp::Reverse(actor)));
+        while let Some(landmark) = next.filter(|_| landmarks.len() < landmark_count) {
+            let from_landmark = actor_distances(graph, landmark);
+            for (actor, &distance) in from_landmark.iter().enumerate() {
+                nearest_landmark[actor] = nearest_landmark[actor].min(distance);
+            }
+            distances.extend(from_landmark.iter().map(|&distance| u8::try_from(distance).unwrap_or(UNREACHABLE_BYTE)));
+            landmarks.push(graph.actor_id(landmark));
+
+            // Farthest reachable actor that isn't a landmark yet (distance 0)
+            next = (0..actor_count)
+                .filter(|&actor| nearest_landmark[actor] != UNREACHABLE && nearest_landmark[actor] > 0)
+                .max_by_key(|&actor| (nearest_landmark[actor], std::cmp::Reverse(actor)));
+        }
+
+        LandmarkIndex {
+            actor_ids: (0..actor_count).map(|actor| graph.actor_id(actor)).collect(),
+            credits_checksum: credits_checksum(graph),
+            landmarks,
+            distances,
+        }
+    }
+
+    pub fn landmark_ids(&self) -> &[i64] {
+        &self.landmarks
+    }
+
+    // Whether the index was built from a graph with exactly these actors and credits.
+    pub fn matches(&self, graph: &ActorGraph) -> bool {
+        self.actor_ids.len() == graph.actor_count()
+            && self.actor_ids.iter().enumerate().all(|(actor, &actor_id)| graph.actor_id(actor) == actor_id)
+            && self.credits_checksum == credits_checksum(graph)
+    }
+
+    fn landmark_distances(&self, actor: usize) -> impl Iterator<Item = Option<u32>> + '_ {
+        let actor_count = self.actor_ids.len();
+        (0..self.landmarks.len()).map(move |landmark| {
+            let distance = self.distances[landmark * actor_count + actor];
+            (distance != UNREACHABLE_BYTE).then_some(distance as u32)
+        })
+    }
+
+    // Lower bound on the hops between two actor indices, or None if some landmark
+    // reaches one but not the other, which puts them in different components.
+    pub fn lower_bound(&self, first: usize, second: usize) -> Option<u32> {
+        let mut bound = 0;
+        for (first_distance, second_distance) in self.landmark_distances(first).zip(self.landmark_distances(second)) {
+            match (first_distance, second_distance) {
+                (Some(a), Some(b)) => bound = bound.max(a.abs_diff(b)),
+                (None, None) => {}
+                _ => return None,
+            }
+        }
+        Some(bound)
+    }
+
+    // Degrees of separation between two actors without searching. None if the actors
+    // are provably not linked or either has no credits.
+    pub fn estimate(&self, graph: &ActorGraph, first_actor_id: i64, second_actor_id: i64) -> Option<SeparationEstimate> {
+        let first = graph.actor_index(first_actor_id)?;
+        let second = graph.actor_index(second_actor_id)?;
+        if first == second {
+            return Some(SeparationEstimate { lower_bound: 0, upper_bound: Some(0) });
+        }
+        let lower_bound = self.lower_bound(first, second)?.max(1);
+        let upper_bound = self
+            .landmark_distances(first)
+            .zip(self.landmark_distances(second))
+            .filter_map(|(a, b)| Some(a? + b?))
+            .min();
+        Some(SeparationEstimate { lower_bound, upper_bound })
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&(self.actor_ids.len() as u32).to_le_bytes())?;
+        writer.write_all(&(self.landmarks.len() as u32).to_le_bytes())?;
+        writer.write_all(&self.credits_checksum.to_le_bytes())?;
+        for actor_id in self.actor_ids.iter().chain(&self.landmarks) {
+            writer.write_all(&actor_id.to_le_bytes())?;
+        }
+        writer.write_all(&self.distances)
+    }
+
+    pub fn read_from(reader: &mut impl Read) -> io::Result<LandmarkIndex> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a landmark index file"));
+        }
+        let actor_count = read_u32(reader)? as usize;
+        let landmark_count = read_u32(reader)? as usize;
+        let credits_checksum = read_u64(reader)?;
+        let actor_ids = (0..actor_count).map(|_| read_i64(reader)).collect::<io::Result<Vec<i64>>>()?;
+        let landmarks = (0..landmark_count).map(|_| read_i64(reader)).collect::<io::Result<Vec<i64>>>()?;
+        let mut distances = vec![0u8; actor_count * landmark_count];
+        reader.read_exact(&mut distances)?;
+        Ok(LandmarkIndex { actor_ids, credits_checksum, landmarks, distances })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    // Fails with InvalidData if the file was built from different actors or credits.
+    pub fn load(path: impl AsRef<Path>, graph: &ActorGraph) -> io::Result<LandmarkIndex> {
+        let index = LandmarkIndex::read_from(&mut BufReader::new(File::open(path)?))?;
+        if !index.matches(graph) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "landmark index is stale; rebuild it"));
+        }
+        Ok(index)
+    }
+}
+
+// FNV-1a over every movie's ID and cast IDs in index order. Stable across builds,
+// unlike std's hashers.
+fn credits_checksum(graph: &ActorGraph) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for movie in 0..graph.movie_count() {
+        let cast = graph.cast_of(movie).iter().map(|&actor| graph.actor_id(actor as usize));
+        for id in std::iter::once(graph.movie_id(movie)).chain(cast) {
+            for byte in id.to_le_bytes() {
+                hash = (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        }
+        hash = (hash ^ 0xff).wrapping_mul(0x0000_0100_0000_01b3); // Marks the end of the cast list
+    }
+    hash
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_i64(reader: &mut impl Read) -> io::Result<i64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(i64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::graph::Credit;
+    use crate::test_support;
+    use rusqlite::Result;
+
+    #[test]
+    fn test_landmark_bounds() -> Result<()> {
+        let conn = test_support::fixture_connection()?;
+        let graph = ActorGraph::load(&conn)?;
+        let index = LandmarkIndex::build(&graph, 3);
+        // Edward Norton has the most credits
+        assert_eq!(index.landmark_ids()[0], 1);
+
+        for first in 0..graph.actor_count() {
+            let distances = actor_distances(&graph, first);
+            for (second, &distance) in distances.iter().enumerate() {
+                match index.lower_bound(first, second) {
+                    Some(bound) => assert!(bound <= distance),
+                    None => assert_eq!(distance, UNREACHABLE),
+                }
+            }
+        }
+
+        // Norton -> Byrne is three hops, and Norton is a landmark
+        let estimate = index.estimate(&graph, 1, 7).unwrap();
+        assert_eq!(estimate, SeparationEstimate { lower_bound: 3, upper_bound: Some(3) });
+        assert_eq!(index.estimate(&graph, 1, 12), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_landmark_index_round_trip() -> Result<()> {
+        let conn = test_support::fixture_connection()?;
+        let graph = ActorGraph::load(&conn)?;
+        let index = LandmarkIndex::build(&graph, DEFAULT_LANDMARK_COUNT);
+
+        let mut bytes = Vec::new();
+        index.write_to(&mut bytes).unwrap();
+        let loaded = LandmarkIndex::read_from(&mut bytes.as_slice()).unwrap();
+        assert_eq!(loaded.landmark_ids(), index.landmark_ids());
+        assert_eq!(loaded.lower_bound(0, 5), index.lower_bound(0, 5));
+        assert!(loaded.matches(&graph));
+
+        let other_graph = ActorGraph::from_credits(vec![Credit { movie_id: 1, actor_id: 1, cast_order: None }]);
+        assert!(!loaded.matches(&other_graph));
+
+        // Same actors, one more credit: Edward Norton joins the island's movie
+        db::insert_movie_actor_link(&conn, 8, 1, None, None)?;
+        assert!(!loaded.matches(&ActorGraph::load(&conn)?));
+        assert!(LandmarkIndex::read_from(&mut &b"not an index"[..]).is_err());
+        Ok(())
+    }
+}
@@ -1,3 +1,17 @@
 pub mod db;
 pub mod tmdb_get;
-pub mod link_finder;
\ No newline at end of file
+pub mod graph;
+pub mod link_finder;
+pub mod centrality;
+pub mod components;
+pub mod landmarks;
+pub mod collaborators;
+pub mod communities;
+pub mod puzzle;
+pub mod chain_check;
+pub mod name_normalize;
+pub mod name_resolver;
+pub mod populate;
+
+#[cfg(test)]
+mod test_support;
@@ -1,63 +1,239 @@
-use rusqlite::{Connection, Result};
-use std::collections::{HashSet, VecDeque, HashMap};
+use crate::graph::{ActorGraph, UNKNOWN_ORDER};
+use crate::landmarks::LandmarkIndex;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashSet, VecDeque, HashMap};
+use std::collections::hash_map::Entry;
+use std::time::Instant;
 
-// Function to get actor IDs from a movie ID
-fn get_actor_ids_for_movie(conn: &Connection, movie_id: i64) -> Result<HashSet<i64>> {
-    let mut stmt = conn.prepare("SELECT actor_id FROM movie_actors WHERE movie_id = ?")?;
-    let mut rows = stmt.query([movie_id])?;
-    let mut actor_ids = HashSet::new();
-    while let Some(row) = rows.next()? {
-        actor_ids.insert(row.get(0)?);
+// Restrictions a link search has to respect. The default allows everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LinkConstraints {
+    pub banned_actor_ids: HashSet<i64>,
+    pub banned_movie_ids: HashSet<i64>,
+    pub min_year: Option<i32>, // Inclusive; movies without a release date are excluded
+    pub max_year: Option<i32>, // Inclusive
+    pub genre_ids: Option<HashSet<i64>>, // Movies must have at least one of these genres
+    pub max_cast_order: Option<u32>, // Only credits billed at this position or higher (0 = top billed)
+}
+
+impl LinkConstraints {
+    fn restricts_movies(&self) -> bool {
+        !self.banned_movie_ids.is_empty() || self.min_year.is_some() || self.max_year.is_some() || self.genre_ids.is_some()
+    }
+}
+
+// LinkConstraints resolved against a graph's indices, answering the per-node
+// checks the searches make while expanding.
+struct SearchFilter<'a> {
+    graph: &'a ActorGraph,
+    banned_actors: HashSet<usize>,
+    allowed_movies: Option<Vec<bool>>, // None when every movie is allowed
+    max_cast_order: Option<u32>,
+}
+
+impl<'a> SearchFilter<'a> {
+    fn new(graph: &'a ActorGraph, constraints: &LinkConstraints) -> SearchFilter<'a> {
+        let banned_actors = constraints.banned_actor_ids.iter().filter_map(|&actor_id| graph.actor_index(actor_id)).collect();
+        let allowed_movies = constraints.restricts_movies().then(|| {
+            (0..graph.movie_count())
+                .map(|movie| {
+                    let info = graph.movie_info(movie);
+                    let in_window = match (constraints.min_year, constraints.max_year, info.release_year) {
+                        (None, None, _) => true,
+                        (_, _, None) => false,
+                        (min_year, max_year, Some(year)) => {
+                            min_year.is_none_or(|min_year| year >= min_year) && max_year.is_none_or(|max_year| year <= max_year)
+                        }
+                    };
+                    let genre_allowed = constraints
+                        .genre_ids
+                        .as_ref()
+                        .is_none_or(|genre_ids| info.genre_ids.iter().any(|genre_id| genre_ids.contains(genre_id)));
+                    in_window && genre_allowed && !constraints.banned_movie_ids.contains(&graph.movie_id(movie))
+                })
+                .collect()
+        });
+        SearchFilter { graph, banned_actors, allowed_movies, max_cast_order: constraints.max_cast_order }
+    }
+
+    fn allows_actor(&self, actor: usize) -> bool {
+        !self.banned_actors.contains(&actor)
+    }
+
+    fn allows_movie(&self, movie: usize) -> bool {
+        self.allowed_movies.as_ref().is_none_or(|allowed| allowed[movie])
+    }
+
+    fn allows_order(&self, cast_order: u32) -> bool {
+        self.max_cast_order.is_none_or(|max_cast_order| cast_order <= max_cast_order)
+    }
+
+    // (movie, cast order) for each credit the actor can be linked through
+    fn credits(&self, actor: usize) -> impl Iterator<Item = (usize, u32)> + '_ {
+        self.graph
+            .movies_of(actor)
+            .iter()
+            .zip(self.graph.movie_orders_of(actor))
+            .filter(|&(&movie, &cast_order)| self.allows_order(cast_order) && self.allows_movie(movie as usize))
+            .map(|(&movie, &cast_order)| (movie as usize, cast_order))
+    }
+
+    // (actor, cast order) for each cast member reachable through an allowed movie
+    fn cast_credits(&self, movie: usize) -> impl Iterator<Item = (usize, u32)> + '_ {
+        self.graph
+            .cast_of(movie)
+            .iter()
+            .zip(self.graph.cast_orders_of(movie))
+            .filter(|&(&actor, &cast_order)| self.allows_order(cast_order) && self.allows_actor(actor as usize))
+            .map(|(&actor, &cast_order)| (actor as usize, cast_order))
+    }
+
+    // Movies the actor can be linked through
+    fn movies(&self, actor: usize) -> impl Iterator<Item = usize> + '_ {
+        self.credits(actor).map(|(movie, _)| movie)
+    }
+
+    // Cast members reachable through an allowed movie
+    fn cast(&self, movie: usize) -> impl Iterator<Item = usize> + '_ {
+        self.cast_credits(movie).map(|(actor, _)| actor)
+    }
+}
+
+// Limits that stop a search early. The default searches until the graph is exhausted.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchOptions {
+    pub max_depth: Option<u32>, // Longest chain, in hops, worth looking for
+    pub max_visited: Option<usize>, // Actors the search may visit before giving up
+    pub deadline: Option<Instant>,
+}
+
+impl SearchOptions {
+    // Why a search that is about to reach `depth` hops with `visited` actors must stop, if it must.
+    fn exceeded(&self, depth: u32, visited: usize) -> Option<AbortReason> {
+        if self.max_depth.is_some_and(|max_depth| depth > max_depth) {
+            Some(AbortReason::MaxDepth)
+        } else if self.max_visited.is_some_and(|max_visited| visited > max_visited) {
+            Some(AbortReason::MaxVisited)
+        } else if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            Some(AbortReason::Timeout)
+        } else {
+            None
+        }
     }
-    Ok(actor_ids)
 }
 
-// Function to get movie IDs from an actor ID
-fn get_movie_ids_for_actor(conn: &Connection, actor_id: i64) -> Result<HashSet<i64>> {
-    let mut stmt = conn.prepare("SELECT movie_id FROM movie_actors WHERE actor_id = ?")?;
-    let mut rows = stmt.query([actor_id])?;
-    let mut movie_ids = HashSet::new();
-    while let Some(row) = rows.next()? {
-        movie_ids.insert(row.get(0)?);
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AbortReason {
+    MaxDepth,
+    MaxVisited,
+    Timeout,
+}
+
+// Result of a search run under SearchOptions. `NotFound` means the actors are
+// definitely not linked; `Aborted` means the search gave up before it could tell.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchOutcome<T> {
+    Found(T),
+    NotFound,
+    Aborted(AbortReason),
+}
+
+impl<T> SearchOutcome<T> {
+    pub fn found(self) -> Option<T> {
+        match self {
+            SearchOutcome::Found(value) => Some(value),
+            SearchOutcome::NotFound | SearchOutcome::Aborted(_) => None,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> SearchOutcome<U> {
+        match self {
+            SearchOutcome::Found(value) => SearchOutcome::Found(f(value)),
+            SearchOutcome::NotFound => SearchOutcome::NotFound,
+            SearchOutcome::Aborted(reason) => SearchOutcome::Aborted(reason),
+        }
     }
-    Ok(movie_ids)
 }
 
 
-pub fn find_actor_link_bidirectional_bfs(conn: &Connection, start_actor_id: i64, target_actor_id: i64) -> Result<Option<Vec<i64>>> {
+pub fn find_actor_link_bidirectional_bfs(graph: &ActorGraph, start_actor_id: i64, target_actor_id: i64) -> Option<Vec<i64>> {
+    find_actor_link_constrained(graph, start_actor_id, target_actor_id, &LinkConstraints::default())
+}
+
+// Bidirectional BFS that only expands through actors, movies and credits the constraints allow.
+pub fn find_actor_link_constrained(
+    graph: &ActorGraph,
+    start_actor_id: i64,
+    target_actor_id: i64,
+    constraints: &LinkConstraints,
+) -> Option<Vec<i64>> {
+    find_actor_link_with_options(graph, start_actor_id, target_actor_id, constraints, &SearchOptions::default()).found()
+}
+
+// Same search as `find_actor_link_constrained`, stopping once any of the options' limits is hit.
+pub fn find_actor_link_with_options(
+    graph: &ActorGraph,
+    start_actor_id: i64,
+    target_actor_id: i64,
+    constraints: &LinkConstraints,
+    options: &SearchOptions,
+) -> SearchOutcome<Vec<i64>> {
+    if constraints.banned_actor_ids.contains(&start_actor_id) || constraints.banned_actor_ids.contains(&target_actor_id) {
+        return SearchOutcome::NotFound;
+    }
     if start_actor_id == target_actor_id {
-        return Ok(Some(vec![start_actor_id])); // Same actor, direct path
+        return SearchOutcome::Found(vec![start_actor_id]); // Same actor, direct path
     }
 
+    // Actors without any credits can't be linked to anyone
+    let (Some(start), Some(target)) = (graph.actor_index(start_actor_id), graph.actor_index(target_actor_id)) else {
+        return SearchOutcome::NotFound;
+    };
+    let filter = SearchFilter::new(graph, constraints);
+    let mut depth = 0; // Levels expanded by both searches together
+
     let mut forward_queue = VecDeque::new();
     let mut backward_queue = VecDeque::new();
     let mut forward_visited = HashSet::new();
     let mut backward_visited = HashSet::new();
-    let mut forward_path = HashMap::new(); // actor_id -> parent_actor_id in forward search
-    let mut backward_path = HashMap::new(); // actor_id -> parent_actor_id in backward search
+    let mut forward_movies_seen = HashSet::new(); // movies already expanded in the forward search
+    let mut backward_movies_seen = HashSet::new(); // movies already expanded in the backward search
+    let mut forward_path = HashMap::new(); // actor index -> parent actor index in forward search
+    let mut backward_path = HashMap::new(); // actor index -> parent actor index in backward search
 
-    forward_queue.push_back(start_actor_id);
-    backward_queue.push_back(target_actor_id);
-    forward_visited.insert(start_actor_id);
-    backward_visited.insert(target_actor_id);
+    forward_queue.push_back(start);
+    backward_queue.push_back(target);
+    forward_visited.insert(start);
+    backward_visited.insert(target);
 
     while !forward_queue.is_empty() && !backward_queue.is_empty() {
         // --- Forward BFS Level ---
+        depth += 1;
         let forward_level_size = forward_queue.len(); // Process current level
         for _ in 0..forward_level_size {
-            if let Some(current_actor_id) = forward_queue.pop_front() {
-                let movie_ids = get_movie_ids_for_actor(conn, current_actor_id)?;
-                for movie_id in movie_ids {
-                    let actor_ids = get_actor_ids_for_movie(conn, movie_id)?;
-                    for neighbor_actor_id in actor_ids {
-                        if !forward_visited.contains(&neighbor_actor_id) {
-                            forward_visited.insert(neighbor_actor_id);
-                            forward_path.insert(neighbor_actor_id, current_actor_id);
-                            forward_queue.push_back(neighbor_actor_id);
-
-                            if backward_visited.contains(&neighbor_actor_id) {
+            if let Some(current_actor) = forward_queue.pop_front() {
+                if let Some(reason) = options.exceeded(depth, forward_visited.len() + backward_visited.len()) {
+                    return SearchOutcome::Aborted(reason);
+                }
+                for movie in filter.movies(current_actor) {
+                    if !forward_movies_seen.insert(movie) {
+                        continue; // Every co-star of this movie is already visited
+                    }
+                    for neighbor_actor in filter.cast(movie) {
+                        if !forward_visited.contains(&neighbor_actor) {
+                            forward_visited.insert(neighbor_actor);
+                            forward_path.insert(neighbor_actor, current_actor);
+                            forward_queue.push_back(neighbor_actor);
+
+                            if backward_visited.contains(&neighbor_actor) {
                                 // Intersection found! Construct path
-                                return construct_path(neighbor_actor_id, &forward_path, &backward_path, start_actor_id, target_actor_id);
+                                return match construct_path(graph, neighbor_actor, &forward_path, &backward_path, start, target) {
+                                    Some(path) => SearchOutcome::Found(path),
+                                    None => SearchOutcome::NotFound,
+                                };
                             }
                         }
                     }
@@ -66,21 +242,29 @@ pub fn find_actor_link_bidirectional_bfs(conn: &Connection, start_actor_id: i64,
         }
 
         // --- Backward BFS Level ---
+        depth += 1;
         let backward_level_size = backward_queue.len(); // Process current level
         for _ in 0..backward_level_size {
-            if let Some(current_actor_id) = backward_queue.pop_front() {
-                let movie_ids = get_movie_ids_for_actor(conn, current_actor_id)?;
-                for movie_id in movie_ids {
-                    let actor_ids = get_actor_ids_for_movie(conn, movie_id)?;
-                    for neighbor_actor_id in actor_ids {
-                        if !backward_visited.contains(&neighbor_actor_id) {
-                            backward_visited.insert(neighbor_actor_id);
-                            backward_path.insert(neighbor_actor_id, current_actor_id);
-                            backward_queue.push_back(neighbor_actor_id);
-
-                            if forward_visited.contains(&neighbor_actor_id) {
+            if let Some(current_actor) = backward_queue.pop_front() {
+                if let Some(reason) = options.exceeded(depth, forward_visited.len() + backward_visited.len()) {
+                    return SearchOutcome::Aborted(reason);
+                }
+                for movie in filter.movies(current_actor) {
+                    if !backward_movies_seen.insert(movie) {
+                        continue; // Every co-star of this movie is already visited
+                    }
+                    for neighbor_actor in filter.cast(movie) {
+                        if !backward_visited.contains(&neighbor_actor) {
+                            backward_visited.insert(neighbor_actor);
+                            backward_path.insert(neighbor_actor, current_actor);
+                            backward_queue.push_back(neighbor_actor);
+
+                            if forward_visited.contains(&neighbor_actor) {
                                 // Intersection found! Construct path
-                                return construct_path(neighbor_actor_id, &forward_path, &backward_path, start_actor_id, target_actor_id);
+                                return match construct_path(graph, neighbor_actor, &forward_path, &backward_path, start, target) {
+                                    Some(path) => SearchOutcome::Found(path),
+                                    None => SearchOutcome::NotFound,
+                                };
                             }
                         }
                     }
@@ -89,79 +273,989 @@ pub fn find_actor_link_bidirectional_bfs(conn: &Connection, start_actor_id: i64,
         }
     }
 
-    Ok(None) // No link found after exploring all reachable actors
+    SearchOutcome::NotFound // No link found after exploring all reachable actors
 }
 
 
 fn construct_path(
-    intersection_actor_id: i64,
-    forward_path: &HashMap<i64, i64>,
-    backward_path: &HashMap<i64, i64>,
-    start_actor_id: i64,
-    target_actor_id: i64,
-) -> Result<Option<Vec<i64>>> {
+    graph: &ActorGraph,
+    intersection_actor: usize,
+    forward_path: &HashMap<usize, usize>,
+    backward_path: &HashMap<usize, usize>,
+    start_actor: usize,
+    target_actor: usize,
+) -> Option<Vec<i64>> {
     let mut path = Vec::new();
 
     // --- Construct path from start actor to intersection actor ---
-    let mut current_id = intersection_actor_id;
-    path.push(current_id);
-    while current_id != start_actor_id {
-        if let Some(parent_id) = forward_path.get(&current_id) { // Use if let to handle Option correctly
-            current_id = *parent_id;
-            path.push(current_id);
-        } else {
-            // This should not happen in a correctly constructed path, but handle error case
-            return Ok(None); // Indicate path construction failure
-        }
+    let mut current = intersection_actor;
+    path.push(current);
+    while current != start_actor {
+        // A missing parent should not happen in a correctly constructed path
+        current = *forward_path.get(&current)?;
+        path.push(current);
     }
     path.reverse(); // Path is constructed backwards, so reverse it
 
     // --- Construct path from intersection actor to target actor ---
-    let mut current_id = intersection_actor_id;
-    let mut backward_path_segment = Vec::new();
-    while current_id != target_actor_id {
-        if let Some(parent_id) = backward_path.get(&current_id) { // Use if let to handle Option correctly
-            current_id = *parent_id;
-            backward_path_segment.push(current_id);
+    let mut current = intersection_actor;
+    while current != target_actor {
+        current = *backward_path.get(&current)?;
+        path.push(current);
+    }
+
+    Some(path.into_iter().map(|actor| graph.actor_id(actor)).collect())
+}
+
+
+// Returns every minimum-length actor chain between the two actors, up to `max_paths`
+// chains, ordered by actor_id along the path. Empty if the actors are not linked.
+pub fn find_all_shortest_links(
+    graph: &ActorGraph,
+    start_actor_id: i64,
+    target_actor_id: i64,
+    max_paths: usize,
+    constraints: &LinkConstraints,
+) -> Vec<Vec<i64>> {
+    find_all_shortest_links_with_options(graph, start_actor_id, target_actor_id, max_paths, constraints, &SearchOptions::default())
+        .found()
+        .unwrap_or_default()
+}
+
+// Same enumeration as `find_all_shortest_links`, stopping once any of the options' limits is hit.
+pub fn find_all_shortest_links_with_options(
+    graph: &ActorGraph,
+    start_actor_id: i64,
+    target_actor_id: i64,
+    max_paths: usize,
+    constraints: &LinkConstraints,
+    options: &SearchOptions,
+) -> SearchOutcome<Vec<Vec<i64>>> {
+    let banned = &constraints.banned_actor_ids;
+    if max_paths == 0 || banned.contains(&start_actor_id) || banned.contains(&target_actor_id) {
+        return SearchOutcome::NotFound;
+    }
+    if start_actor_id == target_actor_id {
+        return SearchOutcome::Found(vec![vec![start_actor_id]]);
+    }
+    let (Some(start), Some(target)) = (graph.actor_index(start_actor_id), graph.actor_index(target_actor_id)) else {
+        return SearchOutcome::NotFound;
+    };
+    let filter = SearchFilter::new(graph, constraints);
+    let mut depth = 0; // Levels expanded by both searches together
+
+    let mut forward_distance = HashMap::from([(start, 0u32)]);
+    let mut backward_distance = HashMap::from([(target, 0u32)]);
+    let mut forward_frontier = vec![start];
+    let mut backward_frontier = vec![target];
+    let mut forward_movies_seen = HashSet::new();
+    let mut backward_movies_seen = HashSet::new();
+
+    // Expand whole levels, always growing the smaller frontier. The first level that
+    // touches the other search contains every meeting point of the shortest paths.
+    let meeting_actors = loop {
+        if forward_frontier.is_empty() || backward_frontier.is_empty() {
+            return SearchOutcome::NotFound; // No link found after exploring all reachable actors
+        }
+        depth += 1;
+        if let Some(reason) = options.exceeded(depth, forward_distance.len() + backward_distance.len()) {
+            return SearchOutcome::Aborted(reason);
+        }
+        let (frontier, distance, movies_seen, other_distance) = if forward_frontier.len() <= backward_frontier.len() {
+            (&mut forward_frontier, &mut forward_distance, &mut forward_movies_seen, &backward_distance)
         } else {
-            // This should not happen in a correctly constructed path, but handle error case
-            return Ok(None); // Indicate path construction failure
+            (&mut backward_frontier, &mut backward_distance, &mut backward_movies_seen, &forward_distance)
+        };
+        *frontier = expand_level(&filter, frontier, distance, movies_seen);
+        let mut meeting: Vec<usize> = frontier.iter().copied().filter(|actor| other_distance.contains_key(actor)).collect();
+        if !meeting.is_empty() {
+            meeting.sort_unstable();
+            break meeting;
+        }
+    };
+
+    let mut paths = Vec::new();
+    for meeting_actor in meeting_actors {
+        let mut prefixes = Vec::new();
+        walk_back(&filter, meeting_actor, &forward_distance, &mut vec![meeting_actor], &mut prefixes, max_paths);
+        let mut suffixes = Vec::new();
+        walk_back(&filter, meeting_actor, &backward_distance, &mut vec![meeting_actor], &mut suffixes, max_paths);
+
+        for prefix in &prefixes {
+            for suffix in &suffixes {
+                // Prefixes end at the start actor and suffixes at the target, both beginning at the meeting actor
+                let path = prefix.iter().rev().chain(suffix.iter().skip(1)).map(|&actor| graph.actor_id(actor)).collect();
+                paths.push(path);
+                if paths.len() >= max_paths {
+                    paths.sort();
+                    return SearchOutcome::Found(paths);
+                }
+            }
+        }
+    }
+    paths.sort();
+    SearchOutcome::Found(paths)
+}
+
+// Visits every unvisited co-star of the frontier and returns them as the next frontier.
+fn expand_level(
+    filter: &SearchFilter,
+    frontier: &[usize],
+    distance: &mut HashMap<usize, u32>,
+    movies_seen: &mut HashSet<usize>,
+) -> Vec<usize> {
+    let mut next_frontier = Vec::new();
+    for &actor in frontier {
+        let next_distance = distance[&actor] + 1;
+        for movie in filter.movies(actor) {
+            if !movies_seen.insert(movie) {
+                continue;
+            }
+            for neighbor_actor in filter.cast(movie) {
+                if let Entry::Vacant(entry) = distance.entry(neighbor_actor) {
+                    entry.insert(next_distance);
+                    next_frontier.push(neighbor_actor);
+                }
+            }
+        }
+    }
+    next_frontier
+}
+
+// Collects chains from `current`'s last actor back to the search origin (distance 0),
+// stepping only to co-stars exactly one level closer.
+fn walk_back(
+    filter: &SearchFilter,
+    actor: usize,
+    distance: &HashMap<usize, u32>,
+    current: &mut Vec<usize>,
+    out: &mut Vec<Vec<usize>>,
+    max_paths: usize,
+) {
+    let actor_distance = distance[&actor];
+    if actor_distance == 0 {
+        out.push(current.clone());
+        return;
+    }
+
+    let mut predecessors: Vec<usize> = filter
+        .movies(actor)
+        .flat_map(|movie| filter.cast(movie))
+        .filter(|neighbor| distance.get(neighbor) == Some(&(actor_distance - 1)))
+        .collect();
+    predecessors.sort_unstable();
+    predecessors.dedup();
+
+    for predecessor in predecessors {
+        if out.len() >= max_paths {
+            return;
+        }
+        current.push(predecessor);
+        walk_back(filter, predecessor, distance, current, out, max_paths);
+        current.pop();
+    }
+}
+
+
+// Yen's algorithm: the `k` shortest loopless actor chains, shortest first. Unlike
+// `find_all_shortest_links` this keeps going into longer chains once the
+// minimum-length ones are exhausted.
+pub fn find_k_shortest_links(
+    graph: &ActorGraph,
+    start_actor_id: i64,
+    target_actor_id: i64,
+    k: usize,
+    constraints: &LinkConstraints,
+) -> Vec<Vec<i64>> {
+    find_k_shortest_links_with_options(graph, start_actor_id, target_actor_id, k, constraints, &SearchOptions::default())
+        .found()
+        .unwrap_or_default()
+}
+
+// Same chains as `find_k_shortest_links`. Every spur search is held to the options'
+// limits; chains longer than `max_depth` are left out rather than aborting the search.
+pub fn find_k_shortest_links_with_options(
+    graph: &ActorGraph,
+    start_actor_id: i64,
+    target_actor_id: i64,
+    k: usize,
+    constraints: &LinkConstraints,
+    options: &SearchOptions,
+) -> SearchOutcome<Vec<Vec<i64>>> {
+    let banned = &constraints.banned_actor_ids;
+    if k == 0 || banned.contains(&start_actor_id) || banned.contains(&target_actor_id) {
+        return SearchOutcome::NotFound;
+    }
+    if start_actor_id == target_actor_id {
+        return SearchOutcome::Found(vec![vec![start_actor_id]]);
+    }
+    let (Some(start), Some(target)) = (graph.actor_index(start_actor_id), graph.actor_index(target_actor_id)) else {
+        return SearchOutcome::NotFound;
+    };
+    let filter = SearchFilter::new(graph, constraints);
+    let first = match shortest_path_avoiding(&filter, start, target, &HashSet::new(), &HashSet::new(), options) {
+        SearchOutcome::Found(path) => path,
+        SearchOutcome::NotFound => return SearchOutcome::NotFound,
+        SearchOutcome::Aborted(reason) => return SearchOutcome::Aborted(reason),
+    };
+
+    let mut accepted: Vec<Vec<usize>> = vec![first];
+    let mut candidates: BTreeSet<(usize, Vec<usize>)> = BTreeSet::new(); // (length, path) so the shortest pops first
+
+    while accepted.len() < k {
+        let previous = accepted.last().unwrap().clone();
+        for spur_position in 0..previous.len() - 1 {
+            let spur_actor = previous[spur_position];
+            let root = &previous[..=spur_position];
+
+            // Don't repeat a hop already taken after this root by an accepted path
+            let mut banned_edges = HashSet::new();
+            for path in &accepted {
+                if path.len() > spur_position + 1 && &path[..=spur_position] == root {
+                    banned_edges.insert((path[spur_position], path[spur_position + 1]));
+                }
+            }
+            // Keep the result loopless by not revisiting the root
+            let banned_actors: HashSet<usize> = root[..spur_position].iter().copied().collect();
+
+            // The root already uses `spur_position` of the hops the options allow
+            let spur_options = SearchOptions { max_depth: options.max_depth.map(|max_depth| max_depth.saturating_sub(spur_position as u32)), ..*options };
+            match shortest_path_avoiding(&filter, spur_actor, target, &banned_actors, &banned_edges, &spur_options) {
+                SearchOutcome::Found(spur_path) => {
+                    let mut candidate = root[..spur_position].to_vec();
+                    candidate.extend(spur_path);
+                    if !accepted.contains(&candidate) {
+                        candidates.insert((candidate.len(), candidate));
+                    }
+                }
+                SearchOutcome::NotFound | SearchOutcome::Aborted(AbortReason::MaxDepth) => {}
+                SearchOutcome::Aborted(reason) => return SearchOutcome::Aborted(reason),
+            }
+        }
+
+        match candidates.pop_first() {
+            Some((_, path)) => accepted.push(path),
+            None => break, // No more distinct chains
+        }
+    }
+
+    SearchOutcome::Found(accepted.into_iter().map(|path| path.into_iter().map(|actor| graph.actor_id(actor)).collect()).collect())
+}
+
+// Up to `k` chains that share no intermediate actors: each search bans every actor
+// used in the middle of the previous chains, so later chains may be longer.
+pub fn find_disjoint_links(
+    graph: &ActorGraph,
+    start_actor_id: i64,
+    target_actor_id: i64,
+    k: usize,
+    constraints: &LinkConstraints,
+) -> Vec<Vec<i64>> {
+    find_disjoint_links_with_options(graph, start_actor_id, target_actor_id, k, constraints, &SearchOptions::default())
+        .found()
+        .unwrap_or_default()
+}
+
+// Same chains as `find_disjoint_links`, each search held to the options' limits. Once
+// the next chain would be longer than `max_depth`, the chains found so far are returned.
+pub fn find_disjoint_links_with_options(
+    graph: &ActorGraph,
+    start_actor_id: i64,
+    target_actor_id: i64,
+    k: usize,
+    constraints: &LinkConstraints,
+    options: &SearchOptions,
+) -> SearchOutcome<Vec<Vec<i64>>> {
+    let banned = &constraints.banned_actor_ids;
+    if k == 0 || banned.contains(&start_actor_id) || banned.contains(&target_actor_id) {
+        return SearchOutcome::NotFound;
+    }
+    if start_actor_id == target_actor_id {
+        return SearchOutcome::Found(vec![vec![start_actor_id]]);
+    }
+    let (Some(start), Some(target)) = (graph.actor_index(start_actor_id), graph.actor_index(target_actor_id)) else {
+        return SearchOutcome::NotFound;
+    };
+    let filter = SearchFilter::new(graph, constraints);
+
+    let mut paths: Vec<Vec<i64>> = Vec::new();
+    let mut banned_actors = HashSet::new();
+    let mut banned_edges = HashSet::new();
+    while paths.len() < k {
+        let path = match shortest_path_avoiding(&filter, start, target, &banned_actors, &banned_edges, options) {
+            SearchOutcome::Found(path) => path,
+            SearchOutcome::NotFound => break,
+            SearchOutcome::Aborted(AbortReason::MaxDepth) if !paths.is_empty() => break,
+            SearchOutcome::Aborted(reason) => return SearchOutcome::Aborted(reason),
+        };
+        if path.len() == 2 {
+            // Direct co-stars have no intermediates; only the hop itself can be excluded
+            banned_edges.insert((start, target));
+        }
+        banned_actors.extend(path[1..path.len() - 1].iter().copied());
+        paths.push(path.into_iter().map(|actor| graph.actor_id(actor)).collect());
+    }
+    if paths.is_empty() {
+        SearchOutcome::NotFound
+    } else {
+        SearchOutcome::Found(paths)
+    }
+}
+
+// Plain BFS from `start` to `target` that never enters `banned_actors` and never takes
+// a hop listed (in either direction) in `banned_edges`, on top of the filter's
+// constraints, stopping once any of the options' limits is hit. Works on actor indices.
+fn shortest_path_avoiding(
+    filter: &SearchFilter,
+    start: usize,
+    target: usize,
+    banned_actors: &HashSet<usize>,
+    banned_edges: &HashSet<(usize, usize)>,
+    options: &SearchOptions,
+) -> SearchOutcome<Vec<usize>> {
+    if start == target {
+        return SearchOutcome::Found(vec![start]);
+    }
+
+    // An actor with a banned hop may not reach every co-star of a movie, so only
+    // expansions from other actors mark the movie as seen.
+    let restricted: HashSet<usize> = banned_edges.iter().flat_map(|&(a, b)| [a, b]).collect();
+    let mut frontier = vec![start];
+    let mut parent = HashMap::from([(start, start)]);
+    let mut movies_seen = HashSet::new();
+    let mut depth = 0;
+
+    while !frontier.is_empty() {
+        depth += 1;
+        let mut next_frontier = Vec::new();
+        for current_actor in frontier {
+            if let Some(reason) = options.exceeded(depth, parent.len()) {
+                return SearchOutcome::Aborted(reason);
+            }
+            let is_restricted = restricted.contains(&current_actor);
+            for movie in filter.movies(current_actor) {
+                if movies_seen.contains(&movie) {
+                    continue; // Every co-star of this movie is already visited
+                }
+                if !is_restricted {
+                    movies_seen.insert(movie);
+                }
+                for neighbor_actor in filter.cast(movie) {
+                    if parent.contains_key(&neighbor_actor)
+                        || banned_actors.contains(&neighbor_actor)
+                        || (is_restricted
+                            && (banned_edges.contains(&(current_actor, neighbor_actor)) || banned_edges.contains(&(neighbor_actor, current_actor))))
+                    {
+                        continue;
+                    }
+                    parent.insert(neighbor_actor, current_actor);
+                    if neighbor_actor == target {
+                        let mut path = vec![target];
+                        let mut current = target;
+                        while current != start {
+                            current = parent[&current];
+                            path.push(current);
+                        }
+                        path.reverse();
+                        return SearchOutcome::Found(path);
+                    }
+                    next_frontier.push(neighbor_actor);
+                }
+            }
+        }
+        frontier = next_frontier;
+    }
+    SearchOutcome::NotFound
+}
+
+// Shortest chain from start to target that passes through `waypoint_actor_id`, built
+// from two shortest segments. The second segment avoids the first one's actors when
+// it can, so the joined chain normally visits every actor once.
+pub fn find_actor_link_via_actor(
+    graph: &ActorGraph,
+    start_actor_id: i64,
+    waypoint_actor_id: i64,
+    target_actor_id: i64,
+    constraints: &LinkConstraints,
+) -> Option<Vec<i64>> {
+    find_actor_link_via_actor_with_options(graph, start_actor_id, waypoint_actor_id, target_actor_id, constraints, &SearchOptions::default()).found()
+}
+
+// Same chain as `find_actor_link_via_actor`, each segment search held to the options' limits.
+pub fn find_actor_link_via_actor_with_options(
+    graph: &ActorGraph,
+    start_actor_id: i64,
+    waypoint_actor_id: i64,
+    target_actor_id: i64,
+    constraints: &LinkConstraints,
+    options: &SearchOptions,
+) -> SearchOutcome<Vec<i64>> {
+    let mut path = match find_actor_link_with_options(graph, start_actor_id, waypoint_actor_id, constraints, options) {
+        SearchOutcome::Found(path) => path,
+        outcome => return outcome,
+    };
+
+    let mut second_constraints = constraints.clone();
+    second_constraints.banned_actor_ids.extend(path[..path.len() - 1].iter().copied());
+    let second_segment = match find_actor_link_with_options(graph, waypoint_actor_id, target_actor_id, &second_constraints, options) {
+        SearchOutcome::Found(segment) => segment,
+        // Going back through the first segment's actors may still give a short enough chain
+        SearchOutcome::NotFound | SearchOutcome::Aborted(AbortReason::MaxDepth) => {
+            match find_actor_link_with_options(graph, waypoint_actor_id, target_actor_id, constraints, options) {
+                SearchOutcome::Found(segment) => segment,
+                outcome => return outcome,
+            }
+        }
+        SearchOutcome::Aborted(reason) => return SearchOutcome::Aborted(reason),
+    };
+
+    path.extend(second_segment.into_iter().skip(1));
+    SearchOutcome::Found(path)
+}
+
+// How many equally short entry/exit pairs to try when avoiding repeated actors.
+const MAX_WAYPOINT_ATTEMPTS: usize = 20;
+
+// Shortest chain from start to target in which one hop goes through `movie_id`.
+// The two actors on either side of that hop are picked to minimise the total length,
+// preferring combinations whose segments don't repeat an actor.
+pub fn find_actor_link_via_movie(
+    graph: &ActorGraph,
+    start_actor_id: i64,
+    movie_id: i64,
+    target_actor_id: i64,
+    constraints: &LinkConstraints,
+) -> Option<Vec<i64>> {
+    find_actor_link_via_movie_with_options(graph, start_actor_id, movie_id, target_actor_id, constraints, &SearchOptions::default()).found()
+}
+
+// Same chain as `find_actor_link_via_movie`, stopping once any of the options' limits is hit.
+pub fn find_actor_link_via_movie_with_options(
+    graph: &ActorGraph,
+    start_actor_id: i64,
+    movie_id: i64,
+    target_actor_id: i64,
+    constraints: &LinkConstraints,
+    options: &SearchOptions,
+) -> SearchOutcome<Vec<i64>> {
+    let banned = &constraints.banned_actor_ids;
+    if banned.contains(&start_actor_id) || banned.contains(&target_actor_id) {
+        return SearchOutcome::NotFound;
+    }
+    let (Some(start), Some(target), Some(movie)) =
+        (graph.actor_index(start_actor_id), graph.actor_index(target_actor_id), graph.movie_index(movie_id))
+    else {
+        return SearchOutcome::NotFound;
+    };
+    let filter = SearchFilter::new(graph, constraints);
+
+    // Cast members who can be linked through the movie under the constraints
+    let cast: Vec<usize> = filter.cast(movie).filter(|&actor| filter.movies(actor).any(|m| m == movie)).collect();
+    let trees = bfs_tree(&filter, start, options).and_then(|from_start| Ok((from_start, bfs_tree(&filter, target, options)?)));
+    let (from_start, from_target) = match trees {
+        Ok(trees) => trees,
+        Err(reason) => return SearchOutcome::Aborted(reason),
+    };
+
+    let mut candidates: Vec<(u32, usize, usize)> = Vec::new(); // (total hops, entry actor, exit actor)
+    for &entry_actor in &cast {
+        for &exit_actor in &cast {
+            if entry_actor == exit_actor {
+                continue;
+            }
+            if let (Some(&(to_entry, _)), Some(&(from_exit, _))) = (from_start.get(&entry_actor), from_target.get(&exit_actor)) {
+                candidates.push((to_entry + 1 + from_exit, entry_actor, exit_actor));
+            }
+        }
+    }
+    candidates.retain(|&(length, _, _)| options.max_depth.is_none_or(|max_depth| length <= max_depth));
+    candidates.sort_unstable();
+    let Some(&(best_length, _, _)) = candidates.first() else {
+        // Trees cut off at `max_depth` may be hiding a longer chain
+        let capped = |tree: &HashMap<usize, (u32, usize)>| options.max_depth.is_some_and(|max_depth| tree.values().any(|&(hops, _)| hops >= max_depth));
+        return if capped(&from_start) || capped(&from_target) { SearchOutcome::Aborted(AbortReason::MaxDepth) } else { SearchOutcome::NotFound };
+    };
+
+    // The trees give one shortest segment per actor; when those overlap, look for an
+    // equally short second segment around the first one before settling for a repeat.
+    let mut path = None;
+    for &(_, entry_actor, exit_actor) in candidates.iter().take_while(|(length, _, _)| *length == best_length).take(MAX_WAYPOINT_ATTEMPTS) {
+        let mut first_segment = tree_path(&from_start, entry_actor);
+        first_segment.reverse(); // start .. entry
+        let used: HashSet<usize> = first_segment.iter().copied().collect();
+        if used.contains(&exit_actor) {
+            continue;
+        }
+        let Some(second_segment) = shortest_path_avoiding(&filter, exit_actor, target, &used, &HashSet::new(), options).found() else {
+            continue;
+        };
+        if second_segment.len() as u32 - 1 == from_target[&exit_actor].0 {
+            first_segment.extend(second_segment);
+            path = Some(first_segment);
+            break;
         }
     }
+    let path = path.unwrap_or_else(|| {
+        let (_, entry_actor, exit_actor) = candidates[0];
+        let mut path = tree_path(&from_start, entry_actor);
+        path.reverse();
+        path.extend(tree_path(&from_target, exit_actor));
+        path
+    });
 
-    path.extend(backward_path_segment); // Append the backward path segment
+    SearchOutcome::Found(path.into_iter().map(|actor| graph.actor_id(actor)).collect())
+}
 
-    Ok(Some(path))
+// Single-source BFS over the reachable graph, no further than the options' `max_depth`:
+// actor -> (hops from source, parent actor). Err when one of the other limits is hit.
+fn bfs_tree(filter: &SearchFilter, source: usize, options: &SearchOptions) -> Result<HashMap<usize, (u32, usize)>, AbortReason> {
+    let mut tree = HashMap::from([(source, (0u32, source))]);
+    let mut queue = VecDeque::from([source]);
+    let mut movies_seen = HashSet::new();
+    while let Some(current_actor) = queue.pop_front() {
+        let next_distance = tree[&current_actor].0 + 1;
+        if options.max_depth.is_some_and(|max_depth| next_distance > max_depth) {
+            continue;
+        }
+        if let Some(reason) = options.exceeded(next_distance, tree.len()) {
+            return Err(reason);
+        }
+        for movie in filter.movies(current_actor) {
+            if !movies_seen.insert(movie) {
+                continue;
+            }
+            for neighbor_actor in filter.cast(movie) {
+                if let Entry::Vacant(entry) = tree.entry(neighbor_actor) {
+                    entry.insert((next_distance, current_actor));
+                    queue.push_back(neighbor_actor);
+                }
+            }
+        }
+    }
+    Ok(tree)
 }
 
+// Actors from `actor` back to the tree's source, inclusive.
+fn tree_path(tree: &HashMap<usize, (u32, usize)>, actor: usize) -> Vec<usize> {
+    let mut path = vec![actor];
+    let mut current = actor;
+    while tree[&current].0 > 0 {
+        current = tree[&current].1;
+        path.push(current);
+    }
+    path
+}
+
+// Same result length as `find_actor_link_with_options`, found with A* guided by the
+// landmark index's lower bounds, so only actors that can lie on a shortest chain are
+// expanded. Constraints only remove links, so the bounds stay valid under them.
+pub fn find_actor_link_landmarks(
+    graph: &ActorGraph,
+    landmarks: &LandmarkIndex,
+    start_actor_id: i64,
+    target_actor_id: i64,
+    constraints: &LinkConstraints,
+    options: &SearchOptions,
+) -> SearchOutcome<Vec<i64>> {
+    if constraints.banned_actor_ids.contains(&start_actor_id) || constraints.banned_actor_ids.contains(&target_actor_id) {
+        return SearchOutcome::NotFound;
+    }
+    if start_actor_id == target_actor_id {
+        return SearchOutcome::Found(vec![start_actor_id]);
+    }
+    let (Some(start), Some(target)) = (graph.actor_index(start_actor_id), graph.actor_index(target_actor_id)) else {
+        return SearchOutcome::NotFound;
+    };
+    let Some(start_bound) = landmarks.lower_bound(start, target) else {
+        return SearchOutcome::NotFound; // Different components
+    };
+    let filter = SearchFilter::new(graph, constraints);
+
+    let mut hops = HashMap::from([(start, 0u32)]);
+    let mut parent: HashMap<usize, usize> = HashMap::new();
+    let mut closed = HashSet::new();
+    // Lowest hop count a movie was expanded at; expanding it again from as far or further adds nothing
+    let mut movie_hops: HashMap<usize, u32> = HashMap::new();
+    // (estimated chain length, remaining bound, actor): ties go to the actor closer to the target
+    let mut heap = BinaryHeap::from([Reverse((start_bound, start_bound, start))]);
+
+    while let Some(Reverse((estimate, _, actor))) = heap.pop() {
+        if !closed.insert(actor) {
+            continue; // Stale heap entry
+        }
+        if let Some(reason) = options.exceeded(estimate, closed.len()) {
+            return SearchOutcome::Aborted(reason);
+        }
+        if actor == target {
+            let mut path = vec![target];
+            while let Some(&previous) = parent.get(path.last().unwrap()) {
+                path.push(previous);
+            }
+            path.reverse();
+            return SearchOutcome::Found(path.into_iter().map(|actor| graph.actor_id(actor)).collect());
+        }
+
+        let next_hops = hops[&actor] + 1;
+        for movie in filter.movies(actor) {
+            if movie_hops.get(&movie).is_some_and(|&known| known <= next_hops) {
+                continue;
+            }
+            movie_hops.insert(movie, next_hops);
+            for neighbor_actor in filter.cast(movie) {
+                if closed.contains(&neighbor_actor) || hops.get(&neighbor_actor).is_some_and(|&known| known <= next_hops) {
+                    continue;
+                }
+                let Some(bound) = landmarks.lower_bound(neighbor_actor, target) else {
+                    continue;
+                };
+                hops.insert(neighbor_actor, next_hops);
+                parent.insert(neighbor_actor, actor);
+                heap.push(Reverse((next_hops + bound, bound, neighbor_actor)));
+            }
+        }
+    }
+    SearchOutcome::NotFound
+}
+
+// Costs for `find_famous_actor_link`, in thousandths of a hop. Entering or leaving a
+// movie costs half a hop, the credit's billing penalty and half the movie's obscurity
+// penalty, so every hop costs at least a full hop and A->B costs the same as B->A.
+const HALF_HOP_COST: u32 = 500;
+const MAX_PENALTY: u32 = 2000;
+
+// Top billing is free; each position further down the cast adds a tenth of a hop.
+fn billing_penalty(cast_order: u32) -> u32 {
+    if cast_order == UNKNOWN_ORDER {
+        MAX_PENALTY
+    } else {
+        cast_order.min(20) * 100
+    }
+}
+
+// Shrinks logarithmically with TMDB popularity; unknown or zero popularity costs the most.
+fn obscurity_penalty(popularity: Option<f64>) -> u32 {
+    match popularity {
+        Some(popularity) if popularity > 0.0 => (MAX_PENALTY as f64 / (1.0 + popularity.ln_1p())) as u32,
+        _ => MAX_PENALTY,
+    }
+}
+
+// Dijkstra over the bipartite graph preferring lead roles in popular movies over a
+// minimal number of hops, so the chain runs through well-known films.
+pub fn find_famous_actor_link(
+    graph: &ActorGraph,
+    start_actor_id: i64,
+    target_actor_id: i64,
+    constraints: &LinkConstraints,
+) -> Option<Vec<i64>> {
+    find_famous_actor_link_with_options(graph, start_actor_id, target_actor_id, constraints, &SearchOptions::default()).found()
+}
+
+// Same search as `find_famous_actor_link`, stopping once any of the options' limits is hit.
+// With a depth limit, the cheapest chain within it is returned, and the search reports
+// `Aborted(MaxDepth)` if the target was not reached within it.
+pub fn find_famous_actor_link_with_options(
+    graph: &ActorGraph,
+    start_actor_id: i64,
+    target_actor_id: i64,
+    constraints: &LinkConstraints,
+    options: &SearchOptions,
+) -> SearchOutcome<Vec<i64>> {
+    let banned = &constraints.banned_actor_ids;
+    if banned.contains(&start_actor_id) || banned.contains(&target_actor_id) {
+        return SearchOutcome::NotFound;
+    }
+    if start_actor_id == target_actor_id {
+        return SearchOutcome::Found(vec![start_actor_id]);
+    }
+    let (Some(start), Some(target)) = (graph.actor_index(start_actor_id), graph.actor_index(target_actor_id)) else {
+        return SearchOutcome::NotFound;
+    };
+    let filter = SearchFilter::new(graph, constraints);
+
+    // Actors are nodes 0..actor_count, movie m is node actor_count + m. With a depth limit,
+    // a node reached cheaply over many hops may still be needed on a shorter, costlier
+    // route, so states are (node, actor hops from the start); without one, hops stay 0.
+    let actor_count = graph.actor_count();
+    let mut cost = HashMap::from([((start, 0u32), 0u32)]);
+    let mut parent: HashMap<(usize, u32), (usize, u32)> = HashMap::new();
+    let mut fewest_settled_hops: HashMap<usize, u32> = HashMap::new();
+    let mut heap = BinaryHeap::from([Reverse((0u32, start, 0u32))]);
+    let mut settled_actors = 0;
+    let mut depth_cut = false;
+
+    while let Some(Reverse((state_cost, node, node_hops))) = heap.pop() {
+        if state_cost > cost[&(node, node_hops)] {
+            continue; // Stale heap entry
+        }
+        // A cheaper state of the same node, reached in no more hops, covers this one
+        if fewest_settled_hops.get(&node).is_some_and(|&hops| hops <= node_hops) {
+            continue;
+        }
+        if node == target {
+            let mut path = vec![graph.actor_id(target)];
+            let mut current = (target, node_hops);
+            while current.0 != start {
+                current = parent[&current];
+                if current.0 < actor_count {
+                    path.push(graph.actor_id(current.0));
+                }
+            }
+            path.reverse();
+            return SearchOutcome::Found(path);
+        }
+        if node < actor_count && !fewest_settled_hops.contains_key(&node) {
+            settled_actors += 1;
+            // Only depth is checked per state; the other limits are global
+            if let Some(reason) = options.exceeded(0, settled_actors) {
+                return SearchOutcome::Aborted(reason);
+            }
+        }
+        fewest_settled_hops.insert(node, node_hops);
+
+        let edges: Vec<(usize, u32)> = if node < actor_count {
+            filter
+                .credits(node)
+                .map(|(movie, cast_order)| {
+                    let step = HALF_HOP_COST + billing_penalty(cast_order) + obscurity_penalty(graph.movie_info(movie).popularity) / 2;
+                    (actor_count + movie, step)
+                })
+                .collect()
+        } else {
+            let movie = node - actor_count;
+            let movie_penalty = obscurity_penalty(graph.movie_info(movie).popularity) / 2;
+            filter
+                .cast_credits(movie)
+                .map(|(actor, cast_order)| (actor, HALF_HOP_COST + billing_penalty(cast_order) + movie_penalty))
+                .collect()
+        };
+        for (next, step) in edges {
+            let next_hops = match options.max_depth {
+                Some(max_depth) if next < actor_count => {
+                    if node_hops >= max_depth {
+                        depth_cut = true;
+                        continue;
+                    }
+                    node_hops + 1
+                }
+                _ => node_hops,
+            };
+            let next_state = (next, next_hops);
+            let next_cost = state_cost + step;
+            if cost.get(&next_state).is_none_or(|&known| next_cost < known) {
+                cost.insert(next_state, next_cost);
+                parent.insert(next_state, (node, node_hops));
+                heap.push(Reverse((next_cost, next, next_hops)));
+            }
+        }
+    }
+    if depth_cut {
+        SearchOutcome::Aborted(AbortReason::MaxDepth)
+    } else {
+        SearchOutcome::NotFound
+    }
+}
+
+// One step of a chain that mixes actors and movies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkNode {
+    Actor(i64),
+    Movie(i64),
+}
+
+// Shortest chain from an actor to a movie: actor, movie, actor, ..., actor, movie,
+// ending with a cast member of the target movie and the movie itself.
+pub fn find_actor_movie_link(
+    graph: &ActorGraph,
+    actor_id: i64,
+    movie_id: i64,
+    constraints: &LinkConstraints,
+    options: &SearchOptions,
+) -> SearchOutcome<Vec<LinkNode>> {
+    if constraints.banned_actor_ids.contains(&actor_id) {
+        return SearchOutcome::NotFound;
+    }
+    let (Some(actor), Some(movie)) = (graph.actor_index(actor_id), graph.movie_index(movie_id)) else {
+        return SearchOutcome::NotFound;
+    };
+    let filter = SearchFilter::new(graph, constraints);
+    chain_to_movie(&filter, &[actor], HashSet::new(), movie, options)
+}
+
+// Shortest chain between two movies: movie, actor, movie, ..., actor, movie.
+pub fn find_movie_link(
+    graph: &ActorGraph,
+    start_movie_id: i64,
+    target_movie_id: i64,
+    constraints: &LinkConstraints,
+    options: &SearchOptions,
+) -> SearchOutcome<Vec<LinkNode>> {
+    let (Some(start), Some(target)) = (graph.movie_index(start_movie_id), graph.movie_index(target_movie_id)) else {
+        return SearchOutcome::NotFound;
+    };
+    let filter = SearchFilter::new(graph, constraints);
+    if !filter.allows_movie(start) {
+        return SearchOutcome::NotFound;
+    }
+    if start == target {
+        return SearchOutcome::Found(vec![LinkNode::Movie(start_movie_id)]);
+    }
+    let sources: Vec<usize> = filter.cast(start).collect();
+    chain_to_movie(&filter, &sources, HashSet::from([start]), target, options).map(|mut chain| {
+        chain.insert(0, LinkNode::Movie(start_movie_id));
+        chain
+    })
+}
+
+// BFS from the source actors to the nearest cast member of `target_movie`, remembering
+// which movie each actor was reached through so the chain can name one per hop.
+fn chain_to_movie(
+    filter: &SearchFilter,
+    sources: &[usize],
+    mut movies_seen: HashSet<usize>,
+    target_movie: usize,
+    options: &SearchOptions,
+) -> SearchOutcome<Vec<LinkNode>> {
+    let graph = filter.graph;
+    if !filter.allows_movie(target_movie) {
+        return SearchOutcome::NotFound;
+    }
+    let target_cast: HashSet<usize> = filter.cast(target_movie).collect();
+    let mut parent: HashMap<usize, Option<(usize, usize)>> = HashMap::new(); // actor -> (previous actor, movie)
+    let mut frontier = Vec::new();
+    for &source in sources {
+        if filter.allows_actor(source) && parent.insert(source, None).is_none() {
+            frontier.push(source);
+        }
+    }
+
+    let mut depth = 0;
+    while !frontier.is_empty() {
+        if let Some(reason) = options.exceeded(depth, parent.len()) {
+            return SearchOutcome::Aborted(reason);
+        }
+        if let Some(&found) = frontier.iter().find(|actor| target_cast.contains(actor)) {
+            let mut chain = vec![LinkNode::Movie(graph.movie_id(target_movie)), LinkNode::Actor(graph.actor_id(found))];
+            let mut current = found;
+            while let Some((previous, movie)) = parent[&current] {
+                chain.push(LinkNode::Movie(graph.movie_id(movie)));
+                chain.push(LinkNode::Actor(graph.actor_id(previous)));
+                current = previous;
+            }
+            chain.reverse();
+            return SearchOutcome::Found(chain);
+        }
+
+        let mut next_frontier = Vec::new();
+        for &actor in &frontier {
+            for movie in filter.movies(actor) {
+                if !movies_seen.insert(movie) {
+                    continue;
+                }
+                for neighbor_actor in filter.cast(movie) {
+                    if let Entry::Vacant(entry) = parent.entry(neighbor_actor) {
+                        entry.insert(Some((actor, movie)));
+                        next_frontier.push(neighbor_actor);
+                    }
+                }
+            }
+        }
+        frontier = next_frontier;
+        depth += 1;
+    }
+    SearchOutcome::NotFound
+}
+
+// Distance `actor_distances` reports for actors in another component.
+pub const UNREACHABLE: u32 = u32::MAX;
+
+// Single-source BFS over the unconstrained graph: hops from `source` to every actor,
+// indexed by actor index.
+pub fn actor_distances(graph: &ActorGraph, source: usize) -> Vec<u32> {
+    let mut distances = vec![UNREACHABLE; graph.actor_count()];
+    let mut movies_seen = vec![false; graph.movie_count()];
+    let mut queue = VecDeque::from([source]);
+    distances[source] = 0;
+    while let Some(current_actor) = queue.pop_front() {
+        let next_distance = distances[current_actor] + 1;
+        for &movie in graph.movies_of(current_actor) {
+            if std::mem::replace(&mut movies_seen[movie as usize], true) {
+                continue;
+            }
+            for &neighbor_actor in graph.cast_of(movie as usize) {
+                if distances[neighbor_actor as usize] == UNREACHABLE {
+                    distances[neighbor_actor as usize] = next_distance;
+                    queue.push_back(neighbor_actor as usize);
+                }
+            }
+        }
+    }
+    distances
+}
+
+// How far every other actor is from one center actor (their "Bacon numbers").
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DistanceDistribution {
+    pub center_actor_id: i64,
+    pub counts: Vec<usize>, // counts[d] = actors exactly d hops away; counts[0] is the center itself
+    pub reachable: usize, // Other actors linked to the center
+    pub unreachable: usize, // Actors in other components
+    pub average_distance: f64, // Over reachable actors; 0 when there are none
+}
+
+// None if the actor has no credits.
+pub fn distance_distribution(graph: &ActorGraph, center_actor_id: i64) -> Option<DistanceDistribution> {
+    let center = graph.actor_index(center_actor_id)?;
+    let mut counts = Vec::new();
+    let mut unreachable = 0;
+    for distance in actor_distances(graph, center) {
+        if distance == UNREACHABLE {
+            unreachable += 1;
+            continue;
+        }
+        let distance = distance as usize;
+        if counts.len() <= distance {
+            counts.resize(distance + 1, 0);
+        }
+        counts[distance] += 1;
+    }
+    let reachable = counts.iter().sum::<usize>() - 1;
+    let total_distance: usize = counts.iter().enumerate().map(|(distance, count)| distance * count).sum();
+    let average_distance = if reachable == 0 { 0.0 } else { total_distance as f64 / reachable as f64 };
+    Some(DistanceDistribution { center_actor_id, counts, reachable, unreachable, average_distance })
+}
+
+// For each hop of an actor chain, the movie IDs linking the two actors that the
+// constraints allow.
+pub fn connecting_movies(graph: &ActorGraph, path: &[i64], constraints: &LinkConstraints) -> Vec<Vec<i64>> {
+    let filter = SearchFilter::new(graph, constraints);
+    path.windows(2)
+        .map(|pair| {
+            let (Some(first), Some(second)) = (graph.actor_index(pair[0]), graph.actor_index(pair[1])) else {
+                return Vec::new();
+            };
+            let second_movies: HashSet<usize> = filter.movies(second).collect();
+            filter.movies(first).filter(|movie| second_movies.contains(movie)).map(|movie| graph.movie_id(movie)).collect()
+        })
+        .collect()
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db;
+    use crate::graph::Credit;
+    use crate::test_support;
+    use rusqlite::Result;
 
-    #[test]
-    fn test_get_actor_ids_for_movie() -> Result<()> {
-        let conn = db::establish_connection()?;
-        // Assuming movie_id 1 (Fight Club) has actors
-        let actor_ids = get_actor_ids_for_movie(&conn, 1)?;
-        assert!(!actor_ids.is_empty());
-        Ok(())
-    }
-
-    #[test]
-    fn test_get_movie_ids_for_actor() -> Result<()> {
-        let conn = db::establish_connection()?;
-        // Assuming actor_id 2 (Brad Pitt) has movies
-        let movie_ids = get_movie_ids_for_actor(&conn, 2)?;
-        assert!(!movie_ids.is_empty());
-        Ok(())
+    fn fixture_graph() -> Result<ActorGraph> {
+        let conn = test_support::fixture_connection()?;
+        ActorGraph::load(&conn)
     }
 
     #[test]
     fn test_find_actor_link_bidirectional_bfs_same_actor() -> Result<()> {
-        let conn = db::establish_connection()?;
-        let path = find_actor_link_bidirectional_bfs(&conn, 2, 2)?; // Brad Pitt to Brad Pitt
+        let graph = fixture_graph()?;
+        let path = find_actor_link_bidirectional_bfs(&graph, 2, 2); // Brad Pitt to Brad Pitt
         assert!(path.is_some());
         assert_eq!(path.unwrap(), vec![2]);
         Ok(())
@@ -169,9 +1263,9 @@ mod tests {
 
     #[test]
     fn test_find_actor_link_bidirectional_bfs() -> Result<()> {
-        let conn = db::establish_connection()?;
-        // Assuming Brad Pitt (2) and Edward Norton (1) are linked (e.g., Fight Club)
-        let path_option = find_actor_link_bidirectional_bfs(&conn, 2, 1)?;
+        let graph = fixture_graph()?;
+        // Brad Pitt (2) and Edward Norton (1) are linked through Fight Club
+        let path_option = find_actor_link_bidirectional_bfs(&graph, 2, 1);
         assert!(path_option.is_some());
         if let Some(path) = path_option {
             println!("Path found: {:?}", path);
@@ -184,21 +1278,335 @@ mod tests {
 
     #[test]
     fn test_find_actor_link_specific_actors() -> Result<()> {
-        let conn = db::establish_connection()?;
+        let graph = fixture_graph()?;
 
-        // Assuming database has data for these actors and "Fight Club"
-        let edward_norton_id = 1; // Replace with actual ID from your DB
-        let helena_bonham_carter_id = 3; // Replace with actual ID
+        let edward_norton_id = 1;
+        let helena_bonham_carter_id = 3;
 
         // Test case: Edward Norton -> Helena Bonham Carter (both in Fight Club with Brad Pitt)
-        let path = find_actor_link_bidirectional_bfs(&conn, edward_norton_id, helena_bonham_carter_id)?;
+        let path = find_actor_link_bidirectional_bfs(&graph, edward_norton_id, helena_bonham_carter_id);
         assert!(path.is_some());
         let path = path.unwrap();
         println!("Path found: {:?}", path);
         assert!(path.contains(&edward_norton_id));
         assert!(path.contains(&helena_bonham_carter_id));
-        assert!(path.len() <= 3); // Expecting a short path (Norton -> Pitt -> HBC or Norton -> HBC directly if they co-starred in another movie in your DB)
+        assert!(path.len() <= 3); // Expecting a short path (Norton -> Pitt -> HBC or Norton -> HBC directly)
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_actor_link_multiple_hops() -> Result<()> {
+        let graph = fixture_graph()?;
+        // Edward Norton -> Brad Pitt -> Kevin Spacey -> Gabriel Byrne
+        let path = find_actor_link_bidirectional_bfs(&graph, 1, 7);
+        assert_eq!(path, Some(vec![1, 2, 6, 7]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_all_shortest_links() -> Result<()> {
+        let graph = fixture_graph()?;
+        // Edward Norton -> Morgan Freeman through Brad Pitt (Se7en) or Tim Robbins (Shawshank)
+        let paths = find_all_shortest_links(&graph, 1, 5, 10, &LinkConstraints::default());
+        assert_eq!(paths, vec![vec![1, 2, 5], vec![1, 10, 5]]);
+
+        let capped = find_all_shortest_links(&graph, 1, 5, 1, &LinkConstraints::default());
+        assert_eq!(capped.len(), 1);
+
+        assert!(find_all_shortest_links(&graph, 1, 12, 10, &LinkConstraints::default()).is_empty());
+        assert_eq!(find_all_shortest_links(&graph, 1, 7, 10, &LinkConstraints::default()), vec![vec![1, 2, 6, 7]]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_k_shortest_links() -> Result<()> {
+        let graph = fixture_graph()?;
+        // Norton -> Pitt: directly (Fight Club), then through Helena Bonham Carter / Meat Loaf,
+        // then longer chains
+        let paths = find_k_shortest_links(&graph, 1, 2, 6, &LinkConstraints::default());
+        assert_eq!(paths.len(), 6);
+        assert_eq!(paths[..3], [vec![1, 2], vec![1, 3, 2], vec![1, 4, 2]]);
+        assert!(paths[3..].iter().all(|path| path.len() == 4));
+        assert!(paths.contains(&vec![1, 10, 5, 2]));
+
+        let first_two = find_k_shortest_links(&graph, 1, 2, 2, &LinkConstraints::default());
+        assert_eq!(first_two, vec![vec![1, 2], vec![1, 3, 2]]);
+        Ok(())
+    }
 
+    #[test]
+    fn test_find_disjoint_links() -> Result<()> {
+        let graph = fixture_graph()?;
+        // Norton -> Spacey: through Pitt, then through Robbins and Freeman
+        let paths = find_disjoint_links(&graph, 1, 6, 5, &LinkConstraints::default());
+        assert_eq!(paths, vec![vec![1, 2, 6], vec![1, 10, 5, 6]]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_path_avoiding() -> Result<()> {
+        let graph = fixture_graph()?;
+        let filter = SearchFilter::new(&graph, &LinkConstraints::default());
+        let index = |actor_id: i64| graph.actor_index(actor_id).unwrap();
+        let ids = |path: Vec<usize>| path.into_iter().map(|actor| graph.actor_id(actor)).collect::<Vec<i64>>();
+        let no_options = SearchOptions::default();
+
+        // Norton can't take the Fight Club hop to Meat Loaf himself, but Pitt still can
+        let banned_edges = HashSet::from([(index(1), index(4))]);
+        let path = shortest_path_avoiding(&filter, index(1), index(4), &HashSet::new(), &banned_edges, &no_options).map(ids);
+        assert_eq!(path, SearchOutcome::Found(vec![1, 2, 4]));
+
+        let one_hop = SearchOptions { max_depth: Some(1), ..Default::default() };
+        let outcome = shortest_path_avoiding(&filter, index(1), index(7), &HashSet::new(), &HashSet::new(), &one_hop);
+        assert_eq!(outcome, SearchOutcome::Aborted(AbortReason::MaxDepth));
+        Ok(())
+    }
+
+    #[test]
+    fn test_constrained_link_banned_actor() -> Result<()> {
+        let graph = fixture_graph()?;
+        // Without Brad Pitt, Norton reaches Spacey through Robbins and Freeman
+        let constraints = LinkConstraints { banned_actor_ids: HashSet::from([2]), ..Default::default() };
+        assert_eq!(find_actor_link_constrained(&graph, 1, 6, &constraints), Some(vec![1, 10, 5, 6]));
+        assert_eq!(find_actor_link_constrained(&graph, 2, 6, &constraints), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_constrained_link_movies() -> Result<()> {
+        let graph = fixture_graph()?;
+        // Norton -> Freeman normally has two routes: Pitt (Se7en) and Robbins (The Incredible Hulk)
+        let nineties = LinkConstraints { min_year: Some(1990), max_year: Some(1999), ..Default::default() };
+        assert_eq!(find_all_shortest_links(&graph, 1, 5, 10, &nineties), vec![vec![1, 2, 5]]);
+
+        let no_se7en = LinkConstraints { banned_movie_ids: HashSet::from([2]), ..Default::default() };
+        assert_eq!(find_all_shortest_links(&graph, 1, 5, 10, &no_se7en), vec![vec![1, 10, 5]]);
+
+        let dramas = LinkConstraints { genre_ids: Some(HashSet::from([18])), ..Default::default() };
+        assert_eq!(find_actor_link_constrained(&graph, 1, 5, &dramas), None);
+        assert_eq!(connecting_movies(&graph, &[1, 2], &dramas), vec![vec![1]]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_constrained_link_billing() -> Result<()> {
+        let graph = fixture_graph()?;
+        // Helena Bonham Carter is billed third in Fight Club, her only movie
+        let top_billed = LinkConstraints { max_cast_order: Some(1), ..Default::default() };
+        assert_eq!(find_actor_link_constrained(&graph, 1, 3, &top_billed), None);
+        assert_eq!(find_actor_link_constrained(&graph, 1, 2, &top_billed), Some(vec![1, 2]));
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_find_actor_link_via_actor() -> Result<()> {
+        let graph = fixture_graph()?;
+        let no_constraints = LinkConstraints::default();
+        // Meat Loaf -> Tim Robbins via Gabriel Byrne: Fight Club, Se7en, The Usual Suspects and back
+        let path = find_actor_link_via_actor(&graph, 4, 7, 10, &no_constraints);
+        assert_eq!(path, Some(vec![4, 2, 6, 7, 6, 5, 10]));
+        // Norton -> Spacey via Morgan Freeman: the second leg avoids going back through Norton
+        let path = find_actor_link_via_actor(&graph, 1, 5, 6, &no_constraints);
+        assert_eq!(path, Some(vec![1, 2, 5, 6]));
+        assert_eq!(find_actor_link_via_actor(&graph, 1, 12, 6, &no_constraints), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_actor_link_via_movie() -> Result<()> {
+        let graph = fixture_graph()?;
+        let no_constraints = LinkConstraints::default();
+        // Helena Bonham Carter -> Morgan Freeman via The Incredible Hulk (7)
+        let path = find_actor_link_via_movie(&graph, 3, 7, 5, &no_constraints);
+        assert_eq!(path, Some(vec![3, 1, 10, 5]));
+        // Pitt -> Freeman via Fight Club: Fight Club must be one of the hops
+        let path = find_actor_link_via_movie(&graph, 2, 1, 5, &no_constraints).unwrap();
+        assert_eq!(path, vec![2, 1, 10, 5]);
+        assert!(connecting_movies(&graph, &path, &no_constraints).iter().any(|movies| movies.contains(&1)));
+        // Banned waypoint movie
+        let no_hulk = LinkConstraints { banned_movie_ids: HashSet::from([7]), ..Default::default() };
+        assert_eq!(find_actor_link_via_movie(&graph, 3, 7, 5, &no_hulk), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_famous_actor_link() -> Result<()> {
+        let graph = fixture_graph()?;
+        let no_constraints = LinkConstraints::default();
+        // Se7en and Fight Club beat the little-watched The Incredible Hulk
+        assert_eq!(find_famous_actor_link(&graph, 1, 5, &no_constraints), Some(vec![1, 2, 5]));
+        let no_se7en = LinkConstraints { banned_movie_ids: HashSet::from([2]), ..Default::default() };
+        assert_eq!(find_famous_actor_link(&graph, 1, 5, &no_se7en), Some(vec![1, 10, 5]));
+        assert_eq!(find_famous_actor_link(&graph, 1, 12, &no_constraints), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_famous_actor_link_prefers_lead_roles() {
+        let credit = |movie_id, actor_id, cast_order| Credit { movie_id, actor_id, cast_order: Some(cast_order) };
+        // Actors 1 and 3 share a movie only as bit parts; both star alongside actor 2
+        let graph = ActorGraph::from_credits(vec![
+            credit(10, 1, 30),
+            credit(10, 3, 31),
+            credit(11, 1, 0),
+            credit(11, 2, 0),
+            credit(12, 2, 0),
+            credit(12, 3, 0),
+        ]);
+        assert_eq!(find_actor_link_bidirectional_bfs(&graph, 1, 3), Some(vec![1, 3]));
+        assert_eq!(find_famous_actor_link(&graph, 1, 3, &LinkConstraints::default()), Some(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_find_famous_actor_link_within_depth() {
+        let credit = |movie_id, actor_id, cast_order| Credit { movie_id, actor_id, cast_order: Some(cast_order) };
+        // 1 -> 2 -> 3 through lead roles is cheaper than 1 -> 3 through bit parts,
+        // but only the bit parts leave a hop for 3 -> 4
+        let graph = ActorGraph::from_credits(vec![
+            credit(10, 1, 30),
+            credit(10, 3, 31),
+            credit(11, 1, 0),
+            credit(11, 2, 0),
+            credit(12, 2, 0),
+            credit(12, 3, 0),
+            credit(13, 3, 0),
+            credit(13, 4, 0),
+        ]);
+        let no_constraints = LinkConstraints::default();
+        assert_eq!(find_famous_actor_link(&graph, 1, 4, &no_constraints), Some(vec![1, 2, 3, 4]));
+        let two_hops = SearchOptions { max_depth: Some(2), ..Default::default() };
+        assert_eq!(find_famous_actor_link_with_options(&graph, 1, 4, &no_constraints, &two_hops), SearchOutcome::Found(vec![1, 3, 4]));
+        let one_hop = SearchOptions { max_depth: Some(1), ..Default::default() };
+        assert_eq!(find_famous_actor_link_with_options(&graph, 1, 4, &no_constraints, &one_hop), SearchOutcome::Aborted(AbortReason::MaxDepth));
+    }
+
+    #[test]
+    fn test_search_options_abort() -> Result<()> {
+        let graph = fixture_graph()?;
+        let no_constraints = LinkConstraints::default();
+        // Norton -> Byrne needs three hops
+        let two_hops = SearchOptions { max_depth: Some(2), ..Default::default() };
+        assert_eq!(find_actor_link_with_options(&graph, 1, 7, &no_constraints, &two_hops), SearchOutcome::Aborted(AbortReason::MaxDepth));
+        assert_eq!(
+            find_all_shortest_links_with_options(&graph, 1, 7, 10, &no_constraints, &two_hops),
+            SearchOutcome::Aborted(AbortReason::MaxDepth)
+        );
+        assert_eq!(
+            find_famous_actor_link_with_options(&graph, 1, 7, &no_constraints, &two_hops),
+            SearchOutcome::Aborted(AbortReason::MaxDepth)
+        );
+        assert_eq!(find_actor_link_with_options(&graph, 1, 5, &no_constraints, &two_hops), SearchOutcome::Found(vec![1, 2, 5]));
+
+        // Chains past the depth limit are left out of the alternatives instead
+        let k_shortest = find_k_shortest_links_with_options(&graph, 1, 2, 6, &no_constraints, &two_hops);
+        assert_eq!(k_shortest, SearchOutcome::Found(vec![vec![1, 2], vec![1, 3, 2], vec![1, 4, 2]]));
+        let disjoint = find_disjoint_links_with_options(&graph, 1, 6, 5, &no_constraints, &two_hops);
+        assert_eq!(disjoint, SearchOutcome::Found(vec![vec![1, 2, 6]]));
+        assert_eq!(
+            find_actor_link_via_actor_with_options(&graph, 4, 7, 10, &no_constraints, &two_hops),
+            SearchOutcome::Aborted(AbortReason::MaxDepth)
+        );
+        assert_eq!(
+            find_actor_link_via_movie_with_options(&graph, 3, 7, 5, &no_constraints, &two_hops),
+            SearchOutcome::Aborted(AbortReason::MaxDepth)
+        );
+
+        let one_actor = SearchOptions { max_visited: Some(1), ..Default::default() };
+        assert_eq!(find_actor_link_with_options(&graph, 1, 7, &no_constraints, &one_actor), SearchOutcome::Aborted(AbortReason::MaxVisited));
+
+        let expired = SearchOptions { deadline: Some(Instant::now()), ..Default::default() };
+        assert_eq!(find_actor_link_with_options(&graph, 1, 7, &no_constraints, &expired), SearchOutcome::Aborted(AbortReason::Timeout));
+        assert_eq!(find_k_shortest_links_with_options(&graph, 1, 2, 6, &no_constraints, &expired), SearchOutcome::Aborted(AbortReason::Timeout));
+
+        // An exhausted search is not an aborted one
+        let generous = SearchOptions { max_depth: Some(10), max_visited: Some(100), deadline: None };
+        assert_eq!(find_actor_link_with_options(&graph, 1, 12, &no_constraints, &generous), SearchOutcome::NotFound);
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_actor_movie_link() -> Result<()> {
+        let graph = fixture_graph()?;
+        let no_options = SearchOptions::default();
+        let no_constraints = LinkConstraints::default();
+        use LinkNode::{Actor, Movie};
+        // Meat Loaf -> The Usual Suspects: Fight Club with Pitt, Se7en with Spacey
+        let chain = find_actor_movie_link(&graph, 4, 3, &no_constraints, &no_options);
+        assert_eq!(chain, SearchOutcome::Found(vec![Actor(4), Movie(1), Actor(2), Movie(2), Actor(6), Movie(3)]));
+        // An actor in the movie links directly
+        assert_eq!(find_actor_movie_link(&graph, 1, 4, &no_constraints, &no_options), SearchOutcome::Found(vec![Actor(1), Movie(4)]));
+        assert_eq!(find_actor_movie_link(&graph, 1, 8, &no_constraints, &no_options), SearchOutcome::NotFound);
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_movie_link() -> Result<()> {
+        let graph = fixture_graph()?;
+        let no_options = SearchOptions::default();
+        let no_constraints = LinkConstraints::default();
+        use LinkNode::{Actor, Movie};
+        // American History X -> The Shawshank Redemption through Norton and Robbins in The Incredible Hulk
+        let chain = find_movie_link(&graph, 4, 5, &no_constraints, &no_options);
+        assert_eq!(chain, SearchOutcome::Found(vec![Movie(4), Actor(1), Movie(7), Actor(10), Movie(5)]));
+        // Fight Club and Se7en share Brad Pitt
+        assert_eq!(find_movie_link(&graph, 1, 2, &no_constraints, &no_options), SearchOutcome::Found(vec![Movie(1), Actor(2), Movie(2)]));
+        assert_eq!(find_movie_link(&graph, 1, 1, &no_constraints, &no_options), SearchOutcome::Found(vec![Movie(1)]));
+        let no_hulk = LinkConstraints { banned_movie_ids: HashSet::from([7]), ..Default::default() };
+        assert_eq!(
+            find_movie_link(&graph, 4, 5, &no_hulk, &no_options),
+            SearchOutcome::Found(vec![Movie(4), Actor(1), Movie(1), Actor(2), Movie(2), Actor(5), Movie(5)])
+        );
+        assert_eq!(find_movie_link(&graph, 1, 8, &no_constraints, &no_options), SearchOutcome::NotFound);
+        Ok(())
+    }
+
+    #[test]
+    fn test_distance_distribution() -> Result<()> {
+        let graph = fixture_graph()?;
+        // From Edward Norton: six co-stars, Freeman and Spacey at two hops, Byrne and del Toro at three
+        let distribution = distance_distribution(&graph, 1).unwrap();
+        assert_eq!(distribution.counts, vec![1, 6, 2, 2]);
+        assert_eq!(distribution.reachable, 10);
+        assert_eq!(distribution.unreachable, 2);
+        assert!((distribution.average_distance - 1.6).abs() < 1e-9);
+
+        let island = distance_distribution(&graph, 12).unwrap();
+        assert_eq!(island.counts, vec![1, 1]);
+        assert_eq!(island.unreachable, 11);
+        assert_eq!(distance_distribution(&graph, 999), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_actor_link_landmarks() -> Result<()> {
+        let graph = fixture_graph()?;
+        let landmarks = LandmarkIndex::build(&graph, 2);
+        let no_constraints = LinkConstraints::default();
+        let no_options = SearchOptions::default();
+        for start_actor_id in 1..=13 {
+            for target_actor_id in 1..=13 {
+                let bfs = find_actor_link_bidirectional_bfs(&graph, start_actor_id, target_actor_id);
+                let alt = find_actor_link_landmarks(&graph, &landmarks, start_actor_id, target_actor_id, &no_constraints, &no_options).found();
+                assert_eq!(alt.as_ref().map(Vec::len), bfs.as_ref().map(Vec::len), "{} -> {}", start_actor_id, target_actor_id);
+            }
+        }
+        // Bounds still hold once constraints remove links
+        let no_pitt = LinkConstraints { banned_actor_ids: HashSet::from([2]), ..Default::default() };
+        assert_eq!(find_actor_link_landmarks(&graph, &landmarks, 1, 6, &no_pitt, &no_options), SearchOutcome::Found(vec![1, 10, 5, 6]));
+        let two_hops = SearchOptions { max_depth: Some(2), ..Default::default() };
+        assert_eq!(
+            find_actor_link_landmarks(&graph, &landmarks, 1, 7, &no_constraints, &two_hops),
+            SearchOutcome::Aborted(AbortReason::MaxDepth)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_actor_link_unreachable() -> Result<()> {
+        let graph = fixture_graph()?;
+        assert_eq!(find_actor_link_bidirectional_bfs(&graph, 1, 12), None);
+        assert_eq!(find_actor_link_bidirectional_bfs(&graph, 1, 999), None);
+        Ok(())
+    }
+}
@@ -3,16 +3,31 @@ use dotenv::dotenv;
 use std::env;
 use std::path::Path;
 //use tokio::time::{sleep, Duration};
-mod tmdb_get;
-mod db;
-mod db_populate;
-mod link_finder;
 use rusqlite::Result;
 use std::sync::Mutex;
-use crate::db::{get_actor_id_by_name, get_actor_name_by_id, get_movie_titles_by_ids, get_movie_ids_for_actor};
-use crate::link_finder::find_actor_link_bidirectional_bfs;
+use std::time::{Duration, Instant};
+use actor_link::db;
+use actor_link::populate::populate_database;
+use actor_link::db::{
+    get_actor_metadata_by_ids, get_actor_name_by_id, get_movie_id_by_title, get_movie_metadata_by_ids, ActorMetadata, MovieMetadata,
+};
 use std::collections::HashSet;
+use actor_link::name_resolver::{exact_actor_matches, resolve_actor_name, NameCandidate, DEFAULT_CANDIDATE_COUNT};
+use actor_link::chain_check::{check_chain, shortest_link_number, HopProblem, SubmittedHop};
+use actor_link::centrality::co_star_degrees;
+use actor_link::collaborators::{suggested_collaborators, top_collaborators, Collaborator, SuggestedCollaborator};
+use actor_link::components::{ComponentSizeCount, Components};
+use actor_link::graph::{parse_release_year, ActorGraph};
+use actor_link::puzzle::{daily_seed, generate_puzzle};
+use actor_link::landmarks::{LandmarkIndex, SeparationEstimate, DEFAULT_INDEX_PATH};
+use actor_link::link_finder::{
+    connecting_movies, distance_distribution, find_actor_link_via_actor_with_options, find_actor_link_via_movie_with_options,
+    find_actor_link_with_options, find_actor_link_landmarks, find_actor_movie_link, find_all_shortest_links_with_options,
+    find_disjoint_links_with_options, find_famous_actor_link_with_options, find_k_shortest_links_with_options, find_movie_link, AbortReason, DistanceDistribution, LinkConstraints, LinkNode, SearchOptions,
+    SearchOutcome,
+};
 use serde::{Serialize, Deserialize}; // Import serde for serialization
+use chrono::{NaiveDate, Utc};
 use actix_cors::Cors;
 use actix_web::http::header;
 
@@ -21,123 +36,1419 @@ async fn ensure_database_exists() -> Result<(), Box<dyn std::error::Error>> {
         println!("Database not found. Setting up and populating database...");
         let conn = db::establish_connection()?;
         db::setup_database(&conn)?;
-        crate::db_populate::populate_database().await?;
+        populate_database().await?;
     }
     Ok(())
 }
 
+const DEFAULT_MAX_PATHS: usize = 10;
+const MAX_PATHS_LIMIT: usize = 100;
+const DEFAULT_SEARCH_TIMEOUT_MS: u64 = 2_000;
+const MAX_SEARCH_TIMEOUT_MS: u64 = 10_000;
+
+#[derive(Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum LinkMode {
+    #[default]
+    Shortest, // Fewest hops
+    Famous, // Lead roles in popular movies, possibly more hops
+}
+
+#[derive(Deserialize, Default)] // Limits on how long a search may run
+struct SearchLimits {
+    max_depth: Option<u32>, // Give up on chains longer than this many hops
+    max_visited: Option<usize>, // Give up after visiting this many actors
+    timeout_ms: Option<u64>, // Give up after this long; defaults to DEFAULT_SEARCH_TIMEOUT_MS
+}
+
 #[derive(Deserialize)] // Struct to deserialize actor names from request
 struct ActorLinkRequest {
-    start_actor_name: String,
-    target_actor_name: String,
+    // Each end is given by actor ID, TMDB person ID or name, checked in that order
+    start_actor_id: Option<i64>,
+    start_tmdb_id: Option<u32>,
+    start_actor_name: Option<String>,
+    target_actor_id: Option<i64>,
+    target_tmdb_id: Option<u32>,
+    target_actor_name: Option<String>,
+    #[serde(default)]
+    all_shortest: bool, // Also return every other chain of the same length
+    max_paths: Option<usize>, // Cap on chains returned with `all_shortest`
+    alternatives: Option<usize>, // Return this many best chains, including longer ones
+    #[serde(default)]
+    disjoint: bool, // With `alternatives`, chains may not share intermediate actors
+    #[serde(default)]
+    constraints: LinkConstraints,
+    #[serde(default)]
+    banned_actor_names: Vec<String>, // Added to `constraints.banned_actor_ids`
+    #[serde(default)]
+    banned_tmdb_ids: Vec<u32>, // Likewise, by TMDB person ID
+    #[serde(default)]
+    mode: LinkMode,
+    #[serde(flatten)]
+    limits: SearchLimits,
+}
+
+#[derive(Deserialize)] // Link search that has to pass through one actor or movie
+struct WaypointLinkRequest {
+    // Actors are given by ID, TMDB person ID or name, as in ActorLinkRequest
+    start_actor_id: Option<i64>,
+    start_tmdb_id: Option<u32>,
+    start_actor_name: Option<String>,
+    target_actor_id: Option<i64>,
+    target_tmdb_id: Option<u32>,
+    target_actor_name: Option<String>,
+    via_actor_id: Option<i64>,
+    via_tmdb_id: Option<u32>,
+    via_actor_name: Option<String>,
+    via_movie_title: Option<String>,
+    #[serde(default)]
+    constraints: LinkConstraints,
+    #[serde(default)]
+    banned_actor_names: Vec<String>,
+    #[serde(default)]
+    banned_tmdb_ids: Vec<u32>,
+    #[serde(flatten)]
+    limits: SearchLimits,
+}
+
+#[derive(Deserialize)] // Chain from an actor to a movie
+struct ActorMovieLinkRequest {
+    // The actor is given by ID, TMDB person ID or name
+    actor_id: Option<i64>,
+    tmdb_id: Option<u32>,
+    actor_name: Option<String>,
+    movie_title: String,
+    #[serde(default)]
+    constraints: LinkConstraints,
+    #[serde(default)]
+    banned_actor_names: Vec<String>,
+    #[serde(default)]
+    banned_tmdb_ids: Vec<u32>,
+    #[serde(flatten)]
+    limits: SearchLimits,
+}
+
+#[derive(Deserialize)] // Chain between two movies
+struct MovieLinkRequest {
+    start_movie_title: String,
+    target_movie_title: String,
+    #[serde(default)]
+    constraints: LinkConstraints,
+    #[serde(default)]
+    banned_actor_names: Vec<String>,
+    #[serde(default)]
+    banned_tmdb_ids: Vec<u32>,
+    #[serde(flatten)]
+    limits: SearchLimits,
+}
+
+#[derive(Deserialize)]
+struct DistributionQuery {
+    // The center actor is given by ID, TMDB person ID or name
+    actor_id: Option<i64>,
+    tmdb_id: Option<u32>,
+    actor_name: Option<String>,
+}
+
+#[derive(Serialize, Default)]
+struct DistributionResponse {
+    actor_name: Option<String>,
+    distribution: Option<DistanceDistribution>,
+    error: Option<String>,
+}
+
+const DEFAULT_LEADERBOARD_SIZE: usize = 20;
+const MAX_LEADERBOARD_SIZE: usize = 500;
+
+#[derive(Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum CentralityRanking {
+    #[default]
+    Closeness,
+    Degree,
+}
+
+#[derive(Deserialize)]
+struct CentralActorsQuery {
+    limit: Option<usize>,
+    #[serde(default)]
+    by: CentralityRanking,
+}
+
+#[derive(Serialize)]
+struct CentralActorEntry {
+    actor_id: i64,
+    name: String,
+    degree: usize,
+    closeness: f64,
+}
+
+#[derive(Serialize, Default)]
+struct CentralActorsResponse {
+    actors: Option< Vec<CentralActorEntry> >,
+    error: Option<String>,
+}
+
+const DEFAULT_COLLABORATOR_COUNT: usize = 20;
+// Movies listed per candidate when a name matches several actors
+const KNOWN_FOR_COUNT: usize = 3;
+
+#[derive(Deserialize)]
+struct ListQuery {
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct Named<T> {
+    name: String,
+    #[serde(flatten)]
+    details: T,
+}
+
+#[derive(Serialize, Default)]
+struct CollaboratorsResponse {
+    actor_id: Option<i64>,
+    name: Option<String>,
+    collaborators: Option< Vec<Named<Collaborator>> >, // Only for /collaborators
+    suggested: Option< Vec<Named<SuggestedCollaborator>> >, // Only for /suggested
+    error: Option<String>,
+}
+
+const DEFAULT_HUB_COUNT: usize = 10;
+
+#[derive(Serialize)]
+struct HubEntry {
+    actor_id: i64,
+    name: String,
+    internal_degree: usize, // Co-stars in the same community
+}
+
+#[derive(Serialize, Default)]
+struct CommunityResponse {
+    actor_id: Option<i64>, // Only for /api/actors/{id}/community
+    name: Option<String>,
+    community_id: Option<i64>,
+    community_size: Option<usize>, // Only for /api/actors/{id}/community
+    hubs: Option< Vec<HubEntry> >,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)] // A player's chain: movies[i] links actors[i] and actors[i + 1]
+struct ChainCheckRequest {
+    actors: Vec<ChainActor>,
+    movies: Vec<String>,
+    #[serde(flatten)]
+    limits: SearchLimits, // For the search computing the optimal length
+}
+
+// An actor in a submitted chain: a name, or {"actor_id": ...} / {"tmdb_id": ...}
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ChainActor {
+    Name(String),
+    Id { actor_id: Option<i64>, tmdb_id: Option<u32> },
+}
+
+#[derive(Serialize)]
+struct InvalidHop {
+    index: usize, // 0 is the hop between actors[0] and actors[1]
+    from_actor: String,
+    movie: String,
+    to_actor: String,
+    problem: HopProblem,
+}
+
+#[derive(Serialize, Default)]
+struct ChainCheckResponse {
+    valid: Option<bool>,
+    link_number: Option<usize>, // Hops in the submitted chain
+    invalid_hop: Option<InvalidHop>,
+    optimal_link_number: Option<usize>, // Hops in a shortest chain between the same two actors
+    is_optimal: Option<bool>, // Only set for valid chains
+    aborted: Option<AbortReason>, // Set when the optimal search hit a limit
+    error: Option<String>,
+}
+
+const DEFAULT_PUZZLE_DEGREES: u32 = 3;
+const MAX_PUZZLE_DEGREES: u32 = 10;
+
+#[derive(Deserialize)]
+struct PuzzleQuery {
+    degrees: Option<u32>,
+    seed: Option<u64>, // Random when missing; the response reports the seed used
+    date: Option<String>, // Daily puzzle only: YYYY-MM-DD, defaults to today (UTC)
+    #[serde(default)]
+    popular_only: bool,
+}
+
+#[derive(Serialize, Default)]
+struct PuzzleResponse {
+    start_actor_id: Option<i64>,
+    start_actor_name: Option<String>,
+    target_actor_id: Option<i64>,
+    target_actor_name: Option<String>,
+    degrees: Option<u32>,
+    seed: Option<u64>,
+    date: Option<String>, // Only for the daily puzzle
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EstimateQuery {
+    // Each end is given by actor ID, TMDB person ID or name, as in ActorLinkRequest
+    start_actor_id: Option<i64>,
+    start_tmdb_id: Option<u32>,
+    start_actor_name: Option<String>,
+    target_actor_id: Option<i64>,
+    target_tmdb_id: Option<u32>,
+    target_actor_name: Option<String>,
+}
+
+#[derive(Serialize, Default)]
+struct EstimateResponse {
+    estimate: Option<SeparationEstimate>,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct GraphStatsResponse {
+    actor_count: usize,
+    movie_count: usize,
+    component_count: usize,
+    largest_component_size: usize,
+    component_sizes: Vec<ComponentSizeCount>, // Largest size first
+}
+
+const DEFAULT_SEARCH_RESULTS: usize = 10;
+const MAX_SEARCH_RESULTS: usize = 50;
+// Shorter queries match too much of the index to rank quickly
+const MIN_SEARCH_QUERY_CHARS: usize = 2;
+
+#[derive(Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum SearchType {
+    #[default]
+    Actor,
+    Movie,
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+    #[serde(default, rename = "type")]
+    search_type: SearchType,
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct ActorSearchEntry {
+    actor_id: i64,
+    name: String,
+    popularity: Option<f64>,
+    credit_count: usize,
+}
+
+#[derive(Serialize)]
+struct MovieSearchEntry {
+    movie_id: i64,
+    title: String,
+    release_year: Option<i32>,
+    popularity: Option<f64>,
+    credit_count: usize,
+}
+
+#[derive(Serialize, Default)] // Only the list for the requested type is set
+struct SearchResponse {
+    actors: Option<Vec<ActorSearchEntry>>,
+    movies: Option<Vec<MovieSearchEntry>>,
+    error: Option<String>,
+}
+
+// Set to 1 to expose the /api/debug routes
+const DEBUG_ROUTES_ENV: &str = "ACTOR_LINK_DEBUG";
+
+#[derive(Serialize, Default)]
+struct QueryPlansResponse {
+    plans: Option<Vec<db::QueryPlan>>,
+    error: Option<String>,
+}
+
+// (previous actor, connecting movie titles, next actor). Actors carry their role in
+// the first listed movie when known, e.g. "Brad Pitt (Tyler Durden)".
+type LinkHop = (String, String, String);
+
+// What clients need to show a movie card, e.g. "Fight Club (1999)" with its poster.
+#[derive(Serialize, Clone)]
+struct MovieEntry {
+    movie_id: i64,
+    title: String,
+    release_year: Option<i32>,
+    release_date: Option<String>,
+    genres: Vec<String>,
+    popularity: Option<f64>,
+    vote_count: Option<u32>,
+    runtime: Option<u32>, // Minutes
+    poster_path: Option<String>, // Relative to TMDB's image base URL
+}
+
+impl From<MovieMetadata> for MovieEntry {
+    fn from(movie: MovieMetadata) -> MovieEntry {
+        MovieEntry {
+            movie_id: movie.movie_id,
+            title: movie.title,
+            release_year: movie.release_date.as_deref().and_then(parse_release_year),
+            release_date: movie.release_date,
+            genres: movie.genres,
+            popularity: movie.popularity,
+            vote_count: movie.vote_count,
+            runtime: movie.runtime,
+            poster_path: movie.poster_path,
+        }
+    }
+}
+
+// An actor with the details `db_populate --enrich-actors` stored; those stay null until it runs.
+#[derive(Serialize, Clone)]
+struct ActorEntry {
+    actor_id: i64,
+    tmdb_actor_id: u32,
+    name: String,
+    known_for_department: Option<String>,
+    profile_path: Option<String>, // Relative to TMDB's image base URL
+    popularity: Option<f64>,
+    gender: Option<&'static str>, // "female", "male" or "non_binary"
+    birthday: Option<String>,
+    deathday: Option<String>,
+    place_of_birth: Option<String>,
+}
+
+impl From<ActorMetadata> for ActorEntry {
+    fn from(actor: ActorMetadata) -> ActorEntry {
+        let gender = match actor.gender {
+            Some(1) => Some("female"),
+            Some(2) => Some("male"),
+            Some(3) => Some("non_binary"),
+            _ => None, // 0 means TMDB doesn't know
+        };
+        ActorEntry {
+            actor_id: actor.actor_id,
+            tmdb_actor_id: actor.tmdb_actor_id,
+            name: actor.name,
+            known_for_department: actor.known_for_department,
+            profile_path: actor.profile_path,
+            popularity: actor.popularity,
+            gender,
+            birthday: actor.birthday,
+            deathday: actor.deathday,
+            place_of_birth: actor.place_of_birth,
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+struct PathDetails {
+    path: Vec<String>,
+    path_actors: Vec<ActorEntry>, // Details for each actor in `path`
+    link_path: Vec<LinkHop>,
+    link_movies: Vec<Vec<MovieEntry>>, // Per hop, the movies behind the titles in `link_path`
+    link_number: usize,
+}
+
+#[derive(Serialize)]
+struct NameSuggestion {
+    actor_id: i64,
+    name: String,
+}
+
+#[derive(Serialize)]
+struct KnownForTitle {
+    movie_id: i64,
+    title: String,
+    release_year: Option<i32>,
+}
+
+// One of several actors sharing a name, with enough to tell them apart.
+#[derive(Serialize)]
+struct ActorCandidate {
+    actor_id: i64,
+    tmdb_actor_id: u32,
+    name: String,
+    known_for_department: Option<String>,
+    credit_count: usize,
+    known_for: Vec<KnownForTitle>,
 }
 
-#[derive(Serialize)] // Struct to serialize the response as JSON
+#[derive(Serialize, Default)] // Struct to serialize the response as JSON
 struct ActorLinkResponse {
     path: Option< Vec<String> >,
-    link_path: Option< Vec< (String, String, String) > >,
+    path_actors: Option< Vec<ActorEntry> >,
+    link_path: Option< Vec<LinkHop> >,
+    link_movies: Option< Vec<Vec<MovieEntry>> >,
     link_number: Option<usize>,
+    all_paths: Option< Vec<PathDetails> >, // Only set when `all_shortest` or `alternatives` was requested
+    aborted: Option<AbortReason>, // Set when the search hit a limit before it could find a link
+    did_you_mean: Option< Vec<NameSuggestion> >, // Close matches when an actor name wasn't found
+    candidates: Option< Vec<ActorCandidate> >, // Actors sharing an ambiguous name, sent with 409 Conflict
     error: Option<String>,
 }
 
+#[derive(Serialize)]
+struct ChainStep {
+    kind: &'static str, // "actor" or "movie"
+    id: i64,
+    name: String,
+    actor: Option<ActorEntry>, // Set on actor steps
+    movie: Option<MovieEntry>, // Set on movie steps
+}
+
+#[derive(Serialize, Default)] // Response for chains that alternate actors and movies
+struct ChainResponse {
+    chain: Option< Vec<ChainStep> >,
+    link_number: Option<usize>, // Links between consecutive steps of `chain`
+    aborted: Option<AbortReason>,
+    error: Option<String>,
+}
+
+// Resolves actor names for a path and the movies connecting each consecutive pair.
+fn describe_path(
+    conn: &rusqlite::Connection,
+    graph: &ActorGraph,
+    path_ids: &[i64],
+    constraints: &LinkConstraints,
+) -> Result<PathDetails> {
+    let actors = get_actor_metadata_by_ids(conn, &path_ids.iter().cloned().collect())?;
+    // Looked up rather than taken: waypoint chains may revisit an actor
+    let path_actors = path_ids
+        .iter()
+        .map(|actor_id| actors.get(actor_id).cloned().map(ActorEntry::from).ok_or(rusqlite::Error::QueryReturnedNoRows))
+        .collect::<Result<Vec<ActorEntry>>>()?;
+    let actor_names_path: Vec<String> = path_actors.iter().map(|actor| actor.name.clone()).collect();
+
+    let mut link_path_details: Vec<LinkHop> = Vec::new(); // For detailed path
+    let mut link_movies: Vec<Vec<MovieEntry>> = Vec::new();
+    for (i, connecting_movie_ids) in connecting_movies(graph, path_ids, constraints).into_iter().enumerate() {
+        let mut connecting_movies_map = get_movie_metadata_by_ids(conn, &connecting_movie_ids.iter().cloned().collect())?;
+        let hop_movies: Vec<MovieEntry> = connecting_movie_ids
+            .iter()
+            .filter_map(|movie_id| connecting_movies_map.remove(movie_id).map(MovieEntry::from))
+            .collect();
+        let movie_titles_string = hop_movies.iter().map(|movie| movie.title.as_str()).collect::<Vec<&str>>().join(", ");
+
+        let (from_actor, to_actor) = match connecting_movie_ids.first() {
+            Some(&movie_id) => (
+                db::actor_name_with_character(conn, &actor_names_path[i], movie_id, path_ids[i])?,
+                db::actor_name_with_character(conn, &actor_names_path[i + 1], movie_id, path_ids[i + 1])?,
+            ),
+            None => (actor_names_path[i].clone(), actor_names_path[i + 1].clone()),
+        };
+        link_path_details.push((from_actor, movie_titles_string, to_actor));
+        link_movies.push(hop_movies);
+    }
+
+    Ok(PathDetails { link_number: path_ids.len() - 1, path: actor_names_path, path_actors, link_path: link_path_details, link_movies })
+}
+
+// "Actor 'brad pit' not found in database. Did you mean 'Brad Pitt'?"
+fn actor_not_found_message(actor_name: &str, suggestions: &[NameCandidate]) -> String {
+    let mut message = format!("Actor '{}' not found in database.", actor_name);
+    if !suggestions.is_empty() {
+        let names: Vec<String> = suggestions.iter().map(|candidate| format!("'{}'", candidate.name)).collect();
+        message.push_str(&format!(" Did you mean {}?", names.join(", ")));
+    }
+    message
+}
+
+// 409 listing the actors an ambiguous name matched, with their best-known movies.
+fn ambiguous_actor_response(conn: &rusqlite::Connection, actor_name: &str, matches: Vec<NameCandidate>) -> HttpResponse {
+    let metadata = get_actor_metadata_by_ids(conn, &matches.iter().map(|candidate| candidate.actor_id).collect());
+    let candidates = metadata.and_then(|mut actors| {
+        matches
+            .into_iter()
+            .map(|candidate| {
+                let actor = actors.remove(&candidate.actor_id).ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+                let known_for = db::get_known_for_movies(conn, candidate.actor_id, KNOWN_FOR_COUNT)?
+                    .into_iter()
+                    .map(|movie| KnownForTitle {
+                        movie_id: movie.movie_id,
+                        release_year: movie.release_date.as_deref().and_then(parse_release_year),
+                        title: movie.title,
+                    })
+                    .collect();
+                Ok(ActorCandidate {
+                    actor_id: candidate.actor_id,
+                    tmdb_actor_id: actor.tmdb_actor_id,
+                    name: actor.name,
+                    known_for_department: actor.known_for_department,
+                    credit_count: candidate.credit_count,
+                    known_for,
+                })
+            })
+            .collect::<Result<Vec<ActorCandidate>>>()
+    });
+    match candidates {
+        Ok(candidates) => HttpResponse::Conflict().json(ActorLinkResponse {
+            error: Some(format!("'{}' matches {} actors; pick one by actor_id or tmdb_id.", actor_name, candidates.len())),
+            candidates: Some(candidates),
+            ..Default::default()
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(ActorLinkResponse {
+            error: Some(format!("Database error when listing actors named '{}': {}", actor_name, e)),
+            ..Default::default()
+        }),
+    }
+}
+
+// Looks up an actor by name, ignoring case and accents; on failure returns the error
+// response to send instead: close matches as suggestions, or every actor sharing the name.
+fn resolve_actor_id(conn: &rusqlite::Connection, actor_name: &str) -> std::result::Result<i64, HttpResponse> {
+    let database_error = |e: rusqlite::Error| {
+        HttpResponse::InternalServerError().json(ActorLinkResponse { // Return error if actor ID retrieval fails
+            error: Some(format!("Database error when fetching actor ID: {}", e)),
+            ..Default::default()
+        })
+    };
+    let mut matches = exact_actor_matches(conn, actor_name).map_err(database_error)?;
+    if matches.len() > 1 {
+        return Err(ambiguous_actor_response(conn, actor_name, matches));
+    }
+    if let Some(only_match) = matches.pop() {
+        return Ok(only_match.actor_id);
+    }
+    let suggestions = resolve_actor_name(conn, actor_name, DEFAULT_CANDIDATE_COUNT).map_err(database_error)?;
+    Err(HttpResponse::NotFound().json(ActorLinkResponse { // Return Not Found if actor is not in DB
+        error: Some(actor_not_found_message(actor_name, &suggestions)),
+        did_you_mean: Some(suggestions.into_iter().map(|candidate| NameSuggestion { actor_id: candidate.actor_id, name: candidate.name }).collect()),
+        ..Default::default()
+    }))
+}
+
+// Looks up an actor by TMDB person ID; on failure returns the error response to send instead.
+fn resolve_tmdb_id(conn: &rusqlite::Connection, tmdb_id: u32) -> std::result::Result<i64, HttpResponse> {
+    match db::get_actor_id_by_tmdb_id(conn, tmdb_id) {
+        Ok(Some(actor_id)) => Ok(actor_id),
+        Ok(None) => Err(HttpResponse::NotFound().json(ActorLinkResponse {
+            error: Some(format!("Actor with TMDB ID {} not found in database.", tmdb_id)),
+            ..Default::default()
+        })),
+        Err(e) => Err(HttpResponse::InternalServerError().json(ActorLinkResponse {
+            error: Some(format!("Database error when fetching actor ID: {}", e)),
+            ..Default::default()
+        })),
+    }
+}
+
+// Resolves an actor given by ID, TMDB person ID or name, checked in that order, to
+// (actor ID, name for messages); on failure returns the error response to send instead.
+// `prefix` names the request fields in messages, e.g. "start_" for start_actor_id.
+fn resolve_link_end(
+    conn: &rusqlite::Connection,
+    prefix: &str,
+    actor_id: Option<i64>,
+    tmdb_id: Option<u32>,
+    actor_name: Option<&str>,
+) -> std::result::Result<(i64, String), HttpResponse> {
+    let actor_id = match (actor_id, tmdb_id, actor_name) {
+        (Some(actor_id), _, _) => actor_id,
+        (None, Some(tmdb_id), _) => resolve_tmdb_id(conn, tmdb_id)?,
+        (None, None, Some(actor_name)) => return resolve_actor_id(conn, actor_name).map(|actor_id| (actor_id, actor_name.to_string())),
+        (None, None, None) => return Err(HttpResponse::BadRequest().json(ActorLinkResponse {
+            error: Some(format!("Give {prefix}actor_id, {prefix}tmdb_id or {prefix}actor_name.")),
+            ..Default::default()
+        })),
+    };
+    match get_actor_name_by_id(conn, actor_id) {
+        Ok(Some(name)) => Ok((actor_id, name)),
+        Ok(None) => Err(HttpResponse::NotFound().json(ActorLinkResponse {
+            error: Some(format!("Actor with ID {} not found in database.", actor_id)),
+            ..Default::default()
+        })),
+        Err(e) => Err(HttpResponse::InternalServerError().json(ActorLinkResponse {
+            error: Some(format!("Database error when fetching actor: {}", e)),
+            ..Default::default()
+        })),
+    }
+}
+
+// Looks up a movie by title; on failure returns the error response to send instead.
+fn resolve_movie_id(conn: &rusqlite::Connection, movie_title: &str) -> std::result::Result<i64, HttpResponse> {
+    match get_movie_id_by_title(conn, movie_title) {
+        Ok(Some(movie_id)) => Ok(movie_id),
+        Ok(None) => Err(HttpResponse::NotFound().json(ActorLinkResponse { // Return Not Found if movie is not in DB
+            error: Some(format!("Movie '{}' not found in database.", movie_title)),
+            ..Default::default()
+        })),
+        Err(e) => Err(HttpResponse::InternalServerError().json(ActorLinkResponse {
+            error: Some(format!("Database error when fetching movie ID: {}", e)),
+            ..Default::default()
+        })),
+    }
+}
+
+fn aborted_message(reason: AbortReason, start_name: &str, target_name: &str) -> String {
+    let detail = match reason {
+        AbortReason::MaxDepth => "depth limit reached",
+        AbortReason::MaxVisited => "node budget exhausted",
+        AbortReason::Timeout => "timed out",
+    };
+    format!("Search aborted before a link between '{}' and '{}' was found ({}).", start_name, target_name, detail)
+}
+
+// Turns the chains a search found into the JSON response. The first chain fills
+// `path`/`link_path`; with `multiple_paths` every chain is also listed in `all_paths`.
+fn path_response(
+    conn: &rusqlite::Connection,
+    graph: &ActorGraph,
+    paths: &[Vec<i64>],
+    multiple_paths: bool,
+    constraints: &LinkConstraints,
+    start_actor_name: &str,
+    target_actor_name: &str,
+) -> HttpResponse {
+    if paths.is_empty() {
+        return HttpResponse::Ok().json(ActorLinkResponse { // Return no path found
+            error: Some(format!("No link found between '{}' and '{}'", start_actor_name, target_actor_name)),
+            ..Default::default()
+        });
+    }
+
+    let described: Result<Vec<PathDetails>> = paths.iter().map(|path_ids| describe_path(conn, graph, path_ids, constraints)).collect();
+    match described {
+        Ok(all_paths) => {
+            let first = all_paths[0].clone();
+            HttpResponse::Ok().json(ActorLinkResponse { // Return path with actor names
+                path: Some(first.path),
+                path_actors: Some(first.path_actors),
+                link_path: Some(first.link_path),
+                link_movies: Some(first.link_movies),
+                link_number: Some(first.link_number),
+                all_paths: multiple_paths.then_some(all_paths),
+                ..Default::default()
+            })
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ActorLinkResponse { // Return error response
+            error: Some(format!("Error finding actor link: {}", e)),
+            ..Default::default()
+        }),
+    }
+}
+
+// Adds actors banned by name or TMDB person ID to the request's constraints.
+fn resolve_constraints(
+    conn: &rusqlite::Connection,
+    constraints: &LinkConstraints,
+    banned_actor_names: &[String],
+    banned_tmdb_ids: &[u32],
+) -> std::result::Result<LinkConstraints, HttpResponse> {
+    let mut constraints = constraints.clone();
+    for banned_actor_name in banned_actor_names {
+        constraints.banned_actor_ids.insert(resolve_actor_id(conn, banned_actor_name)?);
+    }
+    for &banned_tmdb_id in banned_tmdb_ids {
+        constraints.banned_actor_ids.insert(resolve_tmdb_id(conn, banned_tmdb_id)?);
+    }
+    Ok(constraints)
+}
+
+// Search limits for a request; the deadline is counted from now.
+fn search_options(limits: &SearchLimits) -> SearchOptions {
+    let timeout_ms = limits.timeout_ms.unwrap_or(DEFAULT_SEARCH_TIMEOUT_MS).min(MAX_SEARCH_TIMEOUT_MS);
+    SearchOptions {
+        max_depth: limits.max_depth,
+        max_visited: limits.max_visited,
+        deadline: Some(Instant::now() + Duration::from_millis(timeout_ms)),
+    }
+}
+
 async fn get_actor_link(
     req: web::Json<ActorLinkRequest>,
     db_conn: web::Data<Mutex<rusqlite::Connection>>,
+    graph: web::Data<ActorGraph>,
+    components: web::Data<Components>,
+    landmarks: web::Data<Option<LandmarkIndex>>,
 ) -> impl Responder {
-    let start_actor_name = &req.start_actor_name;
-    let target_actor_name = &req.target_actor_name;
-
-    let conn_mutex = db_conn.lock().unwrap();
-    let conn = &conn_mutex;
-
-    let start_actor_id_result = get_actor_id_by_name(conn, start_actor_name);
-    let target_actor_id_result = get_actor_id_by_name(conn, target_actor_name);
-
-    match (start_actor_id_result, target_actor_id_result) {
-        (Ok(Some(start_actor_id)), Ok(Some(target_actor_id))) => {
-            match find_actor_link_bidirectional_bfs(conn, start_actor_id, target_actor_id) {
-                Ok(path_option) => {
-                    match path_option {
-                        Some(path_ids) => {
-                            if path_ids.len() == 1 {
-                                let actor_name = get_actor_name_by_id(conn, path_ids[0]).unwrap().unwrap();
-                                HttpResponse::Ok().json(ActorLinkResponse {
-                                    path: Some(vec![actor_name.clone()]),
-                                    link_path: Some(vec![]),
-                                    link_number: Some(0),
-                                    error: None,
-                                })
-                            } else {
-                                let mut actor_names_path: Vec<String> = Vec::new();
-                                let mut link_path_details: Vec< (String, String, String) > = Vec::new(); // For detailed path
-                                for i in 0..path_ids.len() { // Iterate through actor IDs path
-                                    let actor_id = path_ids[i];
-                                    let actor_name = get_actor_name_by_id(conn, actor_id).unwrap().unwrap();
-                                    actor_names_path.push(actor_name.clone());
-
-                                    if i > 0 {
-                                        let current_actor_id = path_ids[i-1];
-                                        let next_actor_id = path_ids[i];
-
-                                        let current_actor_movies = get_movie_ids_for_actor(conn, current_actor_id).unwrap();
-                                        let next_actor_movies = get_movie_ids_for_actor(conn, next_actor_id).unwrap();
-                                        let connecting_movie_ids: HashSet<i64> = current_actor_movies.intersection(&next_actor_movies).cloned().collect();
-                                        let connecting_movies_map = get_movie_titles_by_ids(conn, &connecting_movie_ids).unwrap();
-                                        let connecting_movie_titles: Vec<&String> = connecting_movies_map.values().collect();
-                                        let connecting_movie_titles_str: Vec<&str> = connecting_movie_titles.iter().map(|s| s.as_str()).collect();
-                                        let movie_titles_string = connecting_movie_titles_str.join(", ");
-
-                                        let prev_actor_name = get_actor_name_by_id(conn, current_actor_id).unwrap().unwrap();
-                                        let next_actor_name = get_actor_name_by_id(conn, next_actor_id).unwrap().unwrap();
-                                        link_path_details.push((prev_actor_name, movie_titles_string, next_actor_name));
-                                    }
-                                }
-                                HttpResponse::Ok().json(ActorLinkResponse { // Return path with actor names
-                                    path: Some(actor_names_path),
-                                    link_path: Some(link_path_details),
-                                    link_number: Some(path_ids.len() - 1),
-                                    error: None,
-                                })
-                            }
-                        },
-                        None => HttpResponse::Ok().json(ActorLinkResponse { // Return no path found
-                            path: None,
-                            link_path: None,
-                            link_number: None,
-                            error: Some(format!("No link found between '{}' and '{}'", start_actor_name, target_actor_name)),
-                        }),
-                    }
-                },
-                Err(e) => HttpResponse::InternalServerError().json(ActorLinkResponse { // Return error response
-                    path: None,
-                    link_path: None,
-                    link_number: None,
-                    error: Some(format!("Error finding actor link: {}", e)),
-                }),
-            }
-        }
-        (Err(e), _) | (_, Err(e)) => HttpResponse::InternalServerError().json(ActorLinkResponse { // Return error if actor ID retrieval fails
-            path: None,
-            link_path: None,
-            link_number: None,
-            error: Some(format!("Database error when fetching actor ID: {}", e)),
+    // Only hold the connection for the lookups; the search itself runs on the in-memory graph
+    let ids = {
+        let conn = db_conn.lock().unwrap();
+        resolve_link_end(&conn, "start_", req.start_actor_id, req.start_tmdb_id, req.start_actor_name.as_deref()).and_then(|start| {
+            let target = resolve_link_end(&conn, "target_", req.target_actor_id, req.target_tmdb_id, req.target_actor_name.as_deref())?;
+            let constraints = resolve_constraints(&conn, &req.constraints, &req.banned_actor_names, &req.banned_tmdb_ids)?;
+            Ok((start, target, constraints))
+        })
+    };
+    let ((start_actor_id, start_actor_name), (target_actor_id, target_actor_name), constraints) = match ids {
+        Ok(ids) => ids,
+        Err(response) => return response,
+    };
+
+    let multiple_paths = req.all_shortest || req.alternatives.is_some();
+    if req.mode == LinkMode::Famous && multiple_paths {
+        return HttpResponse::BadRequest().json(ActorLinkResponse {
+            error: Some("Mode 'famous' returns a single chain; it can't be combined with 'all_shortest' or 'alternatives'.".to_string()),
+            ..Default::default()
+        });
+    }
+
+    if !components.may_link(&graph, start_actor_id, target_actor_id) {
+        return HttpResponse::Ok().json(ActorLinkResponse { // No search needed across components
+            error: Some(format!(
+                "No link found between '{}' and '{}': they are in separate parts of the co-star graph.",
+                start_actor_name, target_actor_name
+            )),
+            ..Default::default()
+        });
+    }
+
+    let options = search_options(&req.limits);
+    let outcome = if req.mode == LinkMode::Famous {
+        find_famous_actor_link_with_options(&graph, start_actor_id, target_actor_id, &constraints, &options).map(|path| vec![path])
+    } else if let Some(alternatives) = req.alternatives {
+        let k = alternatives.clamp(1, MAX_PATHS_LIMIT);
+        if req.disjoint {
+            find_disjoint_links_with_options(&graph, start_actor_id, target_actor_id, k, &constraints, &options)
+        } else {
+            find_k_shortest_links_with_options(&graph, start_actor_id, target_actor_id, k, &constraints, &options)
+        }
+    } else if req.all_shortest {
+        let max_paths = req.max_paths.unwrap_or(DEFAULT_MAX_PATHS).clamp(1, MAX_PATHS_LIMIT);
+        find_all_shortest_links_with_options(&graph, start_actor_id, target_actor_id, max_paths, &constraints, &options)
+    } else if let Some(landmarks) = landmarks.as_ref() {
+        find_actor_link_landmarks(&graph, landmarks, start_actor_id, target_actor_id, &constraints, &options).map(|path| vec![path])
+    } else {
+        find_actor_link_with_options(&graph, start_actor_id, target_actor_id, &constraints, &options).map(|path| vec![path])
+    };
+
+    let paths = match outcome {
+        SearchOutcome::Found(paths) => paths,
+        SearchOutcome::NotFound => Vec::new(),
+        SearchOutcome::Aborted(reason) => {
+            return HttpResponse::Ok().json(ActorLinkResponse { // Distinct from "No link found": a link may still exist
+                aborted: Some(reason),
+                error: Some(aborted_message(reason, &start_actor_name, &target_actor_name)),
+                ..Default::default()
+            });
+        }
+    };
+
+    let conn = db_conn.lock().unwrap();
+    path_response(&conn, &graph, &paths, multiple_paths, &constraints, &start_actor_name, &target_actor_name)
+}
+
+async fn get_actor_link_via(
+    req: web::Json<WaypointLinkRequest>,
+    db_conn: web::Data<Mutex<rusqlite::Connection>>,
+    graph: web::Data<ActorGraph>,
+) -> impl Responder {
+    // Only hold the connection for the lookups; the search itself runs on the in-memory graph
+    let ids = {
+        let conn = db_conn.lock().unwrap();
+        resolve_link_end(&conn, "start_", req.start_actor_id, req.start_tmdb_id, req.start_actor_name.as_deref()).and_then(|start| {
+            let target = resolve_link_end(&conn, "target_", req.target_actor_id, req.target_tmdb_id, req.target_actor_name.as_deref())?;
+            let constraints = resolve_constraints(&conn, &req.constraints, &req.banned_actor_names, &req.banned_tmdb_ids)?;
+            let via_actor_given = req.via_actor_id.is_some() || req.via_tmdb_id.is_some() || req.via_actor_name.is_some();
+            let waypoint = match (via_actor_given, &req.via_movie_title) {
+                (true, None) => {
+                    LinkNode::Actor(resolve_link_end(&conn, "via_", req.via_actor_id, req.via_tmdb_id, req.via_actor_name.as_deref())?.0)
+                }
+                (false, Some(via_movie_title)) => LinkNode::Movie(resolve_movie_id(&conn, via_movie_title)?),
+                _ => return Err(HttpResponse::BadRequest().json(ActorLinkResponse {
+                    error: Some("Give exactly one waypoint: via_actor_id, via_tmdb_id or via_actor_name, or via_movie_title.".to_string()),
+                    ..Default::default()
+                })),
+            };
+            Ok((start, target, waypoint, constraints))
+        })
+    };
+    let ((start_actor_id, start_actor_name), (target_actor_id, target_actor_name), waypoint, constraints) = match ids {
+        Ok(ids) => ids,
+        Err(response) => return response,
+    };
+
+    let options = search_options(&req.limits);
+    let outcome = match waypoint {
+        LinkNode::Actor(via_actor_id) => {
+            find_actor_link_via_actor_with_options(&graph, start_actor_id, via_actor_id, target_actor_id, &constraints, &options)
+        }
+        LinkNode::Movie(via_movie_id) => {
+            find_actor_link_via_movie_with_options(&graph, start_actor_id, via_movie_id, target_actor_id, &constraints, &options)
+        }
+    };
+
+    let paths = match outcome {
+        SearchOutcome::Found(path) => vec![path],
+        SearchOutcome::NotFound => Vec::new(),
+        SearchOutcome::Aborted(reason) => {
+            return HttpResponse::Ok().json(ActorLinkResponse { // Distinct from "No link found": a link may still exist
+                aborted: Some(reason),
+                error: Some(aborted_message(reason, &start_actor_name, &target_actor_name)),
+                ..Default::default()
+            });
+        }
+    };
+
+    let conn = db_conn.lock().unwrap();
+    path_response(&conn, &graph, &paths, false, &constraints, &start_actor_name, &target_actor_name)
+}
+
+
+// Turns an actor/movie chain search outcome into the JSON response.
+fn chain_response(conn: &rusqlite::Connection, outcome: SearchOutcome<Vec<LinkNode>>, start_name: &str, target_name: &str) -> HttpResponse {
+    let chain = match outcome {
+        SearchOutcome::Found(chain) => chain,
+        SearchOutcome::NotFound => {
+            return HttpResponse::Ok().json(ChainResponse {
+                error: Some(format!("No link found between '{}' and '{}'", start_name, target_name)),
+                ..Default::default()
+            })
+        }
+        SearchOutcome::Aborted(reason) => {
+            return HttpResponse::Ok().json(ChainResponse {
+                aborted: Some(reason),
+                error: Some(aborted_message(reason, start_name, target_name)),
+                ..Default::default()
+            })
+        }
+    };
+
+    let (mut actor_ids, mut movie_ids) = (HashSet::new(), HashSet::new());
+    for node in &chain {
+        match *node {
+            LinkNode::Actor(actor_id) => actor_ids.insert(actor_id),
+            LinkNode::Movie(movie_id) => movie_ids.insert(movie_id),
+        };
+    }
+    let metadata = get_actor_metadata_by_ids(conn, &actor_ids).and_then(|actors| Ok((actors, get_movie_metadata_by_ids(conn, &movie_ids)?)));
+    let steps = metadata.and_then(|(mut actors, mut movies)| {
+        chain
+            .iter()
+            .map(|node| match *node {
+                LinkNode::Actor(actor_id) => {
+                    let actor = ActorEntry::from(actors.remove(&actor_id).ok_or(rusqlite::Error::QueryReturnedNoRows)?);
+                    Ok(ChainStep { kind: "actor", id: actor_id, name: actor.name.clone(), actor: Some(actor), movie: None })
+                }
+                LinkNode::Movie(movie_id) => {
+                    let movie = MovieEntry::from(movies.remove(&movie_id).ok_or(rusqlite::Error::QueryReturnedNoRows)?);
+                    Ok(ChainStep { kind: "movie", id: movie_id, name: movie.title.clone(), actor: None, movie: Some(movie) })
+                }
+            })
+            .collect::<Result<Vec<ChainStep>>>()
+    });
+    match steps {
+        Ok(steps) => HttpResponse::Ok().json(ChainResponse {
+            link_number: Some(steps.len() - 1),
+            chain: Some(steps),
+            ..Default::default()
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(ChainResponse {
+            error: Some(format!("Error describing link: {}", e)),
+            ..Default::default()
+        }),
+    }
+}
+
+async fn get_actor_movie_link(
+    req: web::Json<ActorMovieLinkRequest>,
+    db_conn: web::Data<Mutex<rusqlite::Connection>>,
+    graph: web::Data<ActorGraph>,
+) -> impl Responder {
+    let ids = {
+        let conn = db_conn.lock().unwrap();
+        resolve_link_end(&conn, "", req.actor_id, req.tmdb_id, req.actor_name.as_deref()).and_then(|actor| {
+            let movie_id = resolve_movie_id(&conn, &req.movie_title)?;
+            let constraints = resolve_constraints(&conn, &req.constraints, &req.banned_actor_names, &req.banned_tmdb_ids)?;
+            Ok((actor, movie_id, constraints))
+        })
+    };
+    let ((actor_id, actor_name), movie_id, constraints) = match ids {
+        Ok(ids) => ids,
+        Err(response) => return response,
+    };
+
+    let outcome = find_actor_movie_link(&graph, actor_id, movie_id, &constraints, &search_options(&req.limits));
+    let conn = db_conn.lock().unwrap();
+    chain_response(&conn, outcome, &actor_name, &req.movie_title)
+}
+
+async fn get_movie_link(
+    req: web::Json<MovieLinkRequest>,
+    db_conn: web::Data<Mutex<rusqlite::Connection>>,
+    graph: web::Data<ActorGraph>,
+) -> impl Responder {
+    let ids = {
+        let conn = db_conn.lock().unwrap();
+        resolve_movie_id(&conn, &req.start_movie_title).and_then(|start_movie_id| {
+            let target_movie_id = resolve_movie_id(&conn, &req.target_movie_title)?;
+            let constraints = resolve_constraints(&conn, &req.constraints, &req.banned_actor_names, &req.banned_tmdb_ids)?;
+            Ok((start_movie_id, target_movie_id, constraints))
+        })
+    };
+    let (start_movie_id, target_movie_id, constraints) = match ids {
+        Ok(ids) => ids,
+        Err(response) => return response,
+    };
+
+    let outcome = find_movie_link(&graph, start_movie_id, target_movie_id, &constraints, &search_options(&req.limits));
+    let conn = db_conn.lock().unwrap();
+    chain_response(&conn, outcome, &req.start_movie_title, &req.target_movie_title)
+}
+
+// How many actors are 1, 2, 3... hops away from one actor.
+async fn get_distance_distribution(
+    query: web::Query<DistributionQuery>,
+    db_conn: web::Data<Mutex<rusqlite::Connection>>,
+    graph: web::Data<ActorGraph>,
+) -> impl Responder {
+    let (actor_id, actor_name) = {
+        let conn = db_conn.lock().unwrap();
+        match resolve_link_end(&conn, "", query.actor_id, query.tmdb_id, query.actor_name.as_deref()) {
+            Ok(actor) => actor,
+            Err(response) => return response,
+        }
+    };
+
+    match distance_distribution(&graph, actor_id) {
+        Some(distribution) => HttpResponse::Ok().json(DistributionResponse {
+            actor_name: Some(actor_name),
+            distribution: Some(distribution),
+            error: None,
+        }),
+        None => HttpResponse::Ok().json(DistributionResponse {
+            error: Some(format!("Actor '{}' has no credits to link through.", actor_name)),
+            actor_name: Some(actor_name),
+            ..Default::default()
+        }),
+    }
+}
+
+// Leaderboard from the last `graph_stats centrality` run.
+async fn get_central_actors(query: web::Query<CentralActorsQuery>, db_conn: web::Data<Mutex<rusqlite::Connection>>) -> impl Responder {
+    let limit = query.limit.unwrap_or(DEFAULT_LEADERBOARD_SIZE).clamp(1, MAX_LEADERBOARD_SIZE);
+    let conn = db_conn.lock().unwrap();
+    match db::get_central_actors(&conn, query.by == CentralityRanking::Degree, limit) {
+        Ok(actors) => HttpResponse::Ok().json(CentralActorsResponse {
+            actors: Some(
+                actors
+                    .into_iter()
+                    .map(|actor| CentralActorEntry { actor_id: actor.actor_id, name: actor.name, degree: actor.degree, closeness: actor.closeness })
+                    .collect(),
+            ),
+            error: None,
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(CentralActorsResponse {
+            error: Some(format!("Database error when fetching central actors: {}", e)),
+            ..Default::default()
+        }),
+    }
+}
+
+// Pairs each entry with its actor's name.
+fn name_actors<T>(conn: &rusqlite::Connection, entries: Vec<T>, actor_id_of: impl Fn(&T) -> i64) -> Result<Vec<Named<T>>> {
+    entries
+        .into_iter()
+        .map(|details| {
+            let name = get_actor_name_by_id(conn, actor_id_of(&details))?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+            Ok(Named { name, details })
+        })
+        .collect()
+}
+
+// Frequent co-stars (`suggested == false`) or likely-but-missing collaborators for an actor ID.
+// The connection is only held for the name lookups, not while ranking.
+fn collaborators_response(
+    db_conn: &Mutex<rusqlite::Connection>,
+    graph: &ActorGraph,
+    co_star_degrees: &[usize],
+    actor_id: i64,
+    limit: Option<usize>,
+    suggested: bool,
+) -> HttpResponse {
+    let name = match get_actor_name_by_id(&db_conn.lock().unwrap(), actor_id) {
+        Ok(Some(name)) => name,
+        Ok(None) => return HttpResponse::NotFound().json(CollaboratorsResponse {
+            error: Some(format!("Actor with ID {} not found in database.", actor_id)),
+            ..Default::default()
+        }),
+        Err(e) => return HttpResponse::InternalServerError().json(CollaboratorsResponse {
+            error: Some(format!("Database error when fetching actor: {}", e)),
+            ..Default::default()
+        }),
+    };
+
+    let limit = limit.unwrap_or(DEFAULT_COLLABORATOR_COUNT).clamp(1, MAX_LEADERBOARD_SIZE);
+    let mut response = CollaboratorsResponse { actor_id: Some(actor_id), name: Some(name), ..Default::default() };
+    let named = if suggested {
+        let suggestions = suggested_collaborators(graph, co_star_degrees, actor_id, limit);
+        let conn = db_conn.lock().unwrap();
+        name_actors(&conn, suggestions, |entry| entry.actor_id).map(|named| response.suggested = Some(named))
+    } else {
+        let collaborators = top_collaborators(graph, actor_id, limit);
+        let conn = db_conn.lock().unwrap();
+        name_actors(&conn, collaborators, |entry| entry.actor_id).map(|named| response.collaborators = Some(named))
+    };
+    match named {
+        Ok(()) => HttpResponse::Ok().json(response),
+        Err(e) => HttpResponse::InternalServerError().json(CollaboratorsResponse {
+            error: Some(format!("Database error when fetching actor names: {}", e)),
+            ..Default::default()
+        }),
+    }
+}
+
+async fn get_collaborators(
+    path: web::Path<i64>,
+    query: web::Query<ListQuery>,
+    db_conn: web::Data<Mutex<rusqlite::Connection>>,
+    graph: web::Data<ActorGraph>,
+    co_star_degrees: web::Data<Vec<usize>>,
+) -> impl Responder {
+    collaborators_response(&db_conn, &graph, &co_star_degrees, path.into_inner(), query.limit, false)
+}
+
+async fn get_suggested_collaborators(
+    path: web::Path<i64>,
+    query: web::Query<ListQuery>,
+    db_conn: web::Data<Mutex<rusqlite::Connection>>,
+    graph: web::Data<ActorGraph>,
+    co_star_degrees: web::Data<Vec<usize>>,
+) -> impl Responder {
+    collaborators_response(&db_conn, &graph, &co_star_degrees, path.into_inner(), query.limit, true)
+}
+
+// A community's best-connected members, filled into `response`.
+fn community_response(conn: &rusqlite::Connection, mut response: CommunityResponse, community_id: i64, limit: Option<usize>) -> HttpResponse {
+    let limit = limit.unwrap_or(DEFAULT_HUB_COUNT).clamp(1, MAX_LEADERBOARD_SIZE);
+    match db::get_community_hubs(conn, community_id, limit) {
+        Ok(hubs) if hubs.is_empty() => HttpResponse::NotFound().json(CommunityResponse {
+            error: Some(format!("Community {} not found; run `graph_stats communities` first.", community_id)),
+            ..Default::default()
+        }),
+        Ok(hubs) => {
+            response.community_id = Some(community_id);
+            response.hubs = Some(
+                hubs.into_iter().map(|hub| HubEntry { actor_id: hub.actor_id, name: hub.name, internal_degree: hub.internal_degree }).collect(),
+            );
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => HttpResponse::InternalServerError().json(CommunityResponse {
+            error: Some(format!("Database error when fetching community: {}", e)),
+            ..Default::default()
+        }),
+    }
+}
+
+// Which community an actor was placed in, and that community's hubs.
+async fn get_actor_community(
+    path: web::Path<i64>,
+    query: web::Query<ListQuery>,
+    db_conn: web::Data<Mutex<rusqlite::Connection>>,
+) -> impl Responder {
+    let actor_id = path.into_inner();
+    let conn = db_conn.lock().unwrap();
+    let community = get_actor_name_by_id(&conn, actor_id).and_then(|name| Ok((name, db::get_actor_community(&conn, actor_id)?)));
+    match community {
+        Ok((Some(name), Some(community))) => {
+            let response = CommunityResponse {
+                actor_id: Some(actor_id),
+                name: Some(name),
+                community_size: Some(community.community_size),
+                ..Default::default()
+            };
+            community_response(&conn, response, community.community_id, query.limit)
+        }
+        Ok((None, _)) => HttpResponse::NotFound().json(CommunityResponse {
+            error: Some(format!("Actor with ID {} not found in database.", actor_id)),
+            ..Default::default()
+        }),
+        Ok((Some(name), None)) => HttpResponse::NotFound().json(CommunityResponse {
+            error: Some(format!("'{}' has no community; run `graph_stats communities` after populating.", name)),
+            ..Default::default()
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(CommunityResponse {
+            error: Some(format!("Database error when fetching community: {}", e)),
+            ..Default::default()
+        }),
+    }
+}
+
+async fn get_community(path: web::Path<i64>, query: web::Query<ListQuery>, db_conn: web::Data<Mutex<rusqlite::Connection>>) -> impl Responder {
+    let conn = db_conn.lock().unwrap();
+    community_response(&conn, CommunityResponse::default(), path.into_inner(), query.limit)
+}
+
+// Every actor a chain entry may mean, and how to name it in messages. Unknown IDs
+// match no one.
+fn resolve_chain_actor(conn: &rusqlite::Connection, actor: &ChainActor) -> Result<(Vec<i64>, String)> {
+    let actor_id = match *actor {
+        // No suggestions are shown here, so typos needn't be looked up. Every actor
+        // sharing the name is kept; a hop holds if any of them fits.
+        ChainActor::Name(ref actor_name) => {
+            let actor_ids = exact_actor_matches(conn, actor_name)?.into_iter().map(|candidate| candidate.actor_id).collect();
+            return Ok((actor_ids, actor_name.clone()));
+        }
+        ChainActor::Id { actor_id: Some(actor_id), .. } => actor_id,
+        ChainActor::Id { actor_id: None, tmdb_id: Some(tmdb_id) } => match db::get_actor_id_by_tmdb_id(conn, tmdb_id)? {
+            Some(actor_id) => actor_id,
+            None => return Ok((Vec::new(), format!("TMDB ID {}", tmdb_id))),
+        },
+        ChainActor::Id { actor_id: None, tmdb_id: None } => return Ok((Vec::new(), String::new())),
+    };
+    Ok(match get_actor_name_by_id(conn, actor_id)? {
+        Some(name) => (vec![actor_id], name),
+        None => (Vec::new(), format!("actor ID {}", actor_id)),
+    })
+}
+
+// Checks each hop of a player's chain and compares its length to the optimum.
+async fn validate_chain(
+    req: web::Json<ChainCheckRequest>,
+    db_conn: web::Data<Mutex<rusqlite::Connection>>,
+    graph: web::Data<ActorGraph>,
+) -> impl Responder {
+    if req.actors.len() < 2 || req.movies.len() + 1 != req.actors.len() {
+        return HttpResponse::BadRequest().json(ChainCheckResponse {
+            error: Some("A chain needs at least two actors and exactly one movie between each pair.".to_string()),
+            ..Default::default()
+        });
+    }
+
+    if req.actors.iter().any(|actor| matches!(actor, ChainActor::Id { actor_id: None, tmdb_id: None })) {
+        return HttpResponse::BadRequest().json(ChainCheckResponse {
+            error: Some("Give each actor as a name, an actor_id or a tmdb_id.".to_string()),
+            ..Default::default()
+        });
+    }
+
+    // Unknown actors become invalid hops rather than errors
+    let resolved = {
+        let conn = db_conn.lock().unwrap();
+        req.actors
+            .iter()
+            .map(|actor| resolve_chain_actor(&conn, actor))
+            .collect::<Result<Vec<(Vec<i64>, String)>>>()
+            .and_then(|actors| {
+                let movie_ids = req.movies.iter().map(|title| db::get_movie_ids_by_title(&conn, title)).collect::<Result<Vec<Vec<i64>>>>()?;
+                Ok((actors, movie_ids))
+            })
+    };
+    let (actors, movie_ids) = match resolved {
+        Ok(resolved) => resolved,
+        Err(e) => return HttpResponse::InternalServerError().json(ChainCheckResponse {
+            error: Some(format!("Database error when resolving the chain: {}", e)),
+            ..Default::default()
+        }),
+    };
+    let (actor_ids, actor_labels): (Vec<Vec<i64>>, Vec<String>) = actors.into_iter().unzip();
+
+    let hops: Vec<SubmittedHop> = movie_ids
+        .into_iter()
+        .enumerate()
+        .map(|(i, movie_ids)| SubmittedHop { from_actor_ids: actor_ids[i].clone(), movie_ids, to_actor_ids: actor_ids[i + 1].clone() })
+        .collect();
+    let checked = check_chain(&graph, &hops);
+    let invalid_hop = checked.as_ref().err().map(|&(index, problem)| InvalidHop {
+        index,
+        from_actor: actor_labels[index].clone(),
+        movie: req.movies[index].clone(),
+        to_actor: actor_labels[index + 1].clone(),
+        problem,
+    });
+
+    let mut response = ChainCheckResponse { valid: Some(invalid_hop.is_none()), link_number: Some(hops.len()), ..Default::default() };
+    // A valid chain is compared against the actors it actually links; otherwise every
+    // actor sharing an end's name is a candidate and the closest pair counts
+    let pairs = checked.unwrap_or_else(|_| {
+        let target_actor_ids = &actor_ids[actor_ids.len() - 1];
+        actor_ids[0].iter().flat_map(|&start| target_actor_ids.iter().map(move |&target| (start, target))).collect()
+    });
+    match shortest_link_number(&graph, &pairs, &search_options(&req.limits)) {
+        SearchOutcome::Found(optimal_link_number) => {
+            response.optimal_link_number = Some(optimal_link_number);
+            response.is_optimal = invalid_hop.is_none().then_some(optimal_link_number == hops.len());
+        }
+        SearchOutcome::NotFound => {}
+        SearchOutcome::Aborted(reason) => response.aborted = Some(reason),
+    }
+    response.invalid_hop = invalid_hop;
+    HttpResponse::Ok().json(response)
+}
+
+// Generates the puzzle for `seed` and fills in the actor names.
+fn puzzle_response(
+    db_conn: &Mutex<rusqlite::Connection>,
+    graph: &ActorGraph,
+    query: &PuzzleQuery,
+    seed: u64,
+    date: Option<String>,
+) -> HttpResponse {
+    let degrees = query.degrees.unwrap_or(DEFAULT_PUZZLE_DEGREES);
+    if !(1..=MAX_PUZZLE_DEGREES).contains(&degrees) {
+        return HttpResponse::BadRequest().json(PuzzleResponse {
+            error: Some(format!("'degrees' must be between 1 and {}.", MAX_PUZZLE_DEGREES)),
+            ..Default::default()
+        });
+    }
+    let Some(puzzle) = generate_puzzle(graph, degrees, seed, query.popular_only) else {
+        return HttpResponse::Ok().json(PuzzleResponse {
+            degrees: Some(degrees),
+            seed: Some(seed),
+            date,
+            error: Some(format!("Couldn't find two actors exactly {} degrees apart.", degrees)),
+            ..Default::default()
+        });
+    };
+
+    let conn = db_conn.lock().unwrap();
+    let names = get_actor_name_by_id(&conn, puzzle.start_actor_id)
+        .and_then(|start_actor_name| Ok((start_actor_name, get_actor_name_by_id(&conn, puzzle.target_actor_id)?)));
+    match names {
+        Ok((start_actor_name, target_actor_name)) => HttpResponse::Ok().json(PuzzleResponse {
+            start_actor_id: Some(puzzle.start_actor_id),
+            start_actor_name,
+            target_actor_id: Some(puzzle.target_actor_id),
+            target_actor_name,
+            degrees: Some(degrees),
+            seed: Some(seed),
+            date,
+            error: None,
         }),
-        (Ok(None), _) => HttpResponse::NotFound().json(ActorLinkResponse { // Return Not Found if start actor is not in DB
-            path: None,
-            link_path: None,
-            link_number: None,
-            error: Some(format!("Actor '{}' not found in database.", start_actor_name)),
+        Err(e) => HttpResponse::InternalServerError().json(PuzzleResponse {
+            error: Some(format!("Database error when fetching actor names: {}", e)),
+            ..Default::default()
         }),
-        (_, Ok(None)) => HttpResponse::NotFound().json(ActorLinkResponse { // Return Not Found if target actor is not in DB
-            path: None,
-            link_path: None,
-            link_number: None,
-            error: Some(format!("Actor '{}' not found in database.", target_actor_name)),
+    }
+}
+
+// Two actors exactly `degrees` apart; the same seed always gives the same pair.
+async fn get_puzzle(
+    query: web::Query<PuzzleQuery>,
+    db_conn: web::Data<Mutex<rusqlite::Connection>>,
+    graph: web::Data<ActorGraph>,
+) -> impl Responder {
+    // Kept to 32 bits so JavaScript clients can pass it back unchanged
+    let seed = query.seed.unwrap_or_else(|| rand::random::<u32>() as u64);
+    puzzle_response(&db_conn, &graph, &query, seed, None)
+}
+
+// Everyone gets the same puzzle on the same date.
+async fn get_daily_puzzle(
+    query: web::Query<PuzzleQuery>,
+    db_conn: web::Data<Mutex<rusqlite::Connection>>,
+    graph: web::Data<ActorGraph>,
+) -> impl Responder {
+    let date = match &query.date {
+        Some(date) => match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            Ok(date) => date,
+            Err(_) => return HttpResponse::BadRequest().json(PuzzleResponse {
+                error: Some(format!("Invalid date '{}'; expected YYYY-MM-DD.", date)),
+                ..Default::default()
+            }),
+        },
+        None => Utc::now().date_naive(),
+    };
+    puzzle_response(&db_conn, &graph, &query, daily_seed(date), Some(date.format("%Y-%m-%d").to_string()))
+}
+
+// Degrees of separation bounded from the landmark index, without running a search.
+async fn get_estimated_degrees(
+    query: web::Query<EstimateQuery>,
+    db_conn: web::Data<Mutex<rusqlite::Connection>>,
+    graph: web::Data<ActorGraph>,
+    landmarks: web::Data<Option<LandmarkIndex>>,
+) -> impl Responder {
+    let Some(landmarks) = landmarks.as_ref() else {
+        return HttpResponse::ServiceUnavailable().json(EstimateResponse {
+            error: Some("No landmark index is loaded; run `graph_stats landmarks` and restart the server.".to_string()),
+            ..Default::default()
+        });
+    };
+    let ids = {
+        let conn = db_conn.lock().unwrap();
+        resolve_link_end(&conn, "start_", query.start_actor_id, query.start_tmdb_id, query.start_actor_name.as_deref()).and_then(|start| {
+            Ok((start, resolve_link_end(&conn, "target_", query.target_actor_id, query.target_tmdb_id, query.target_actor_name.as_deref())?))
+        })
+    };
+    let ((start_actor_id, start_actor_name), (target_actor_id, target_actor_name)) = match ids {
+        Ok(ids) => ids,
+        Err(response) => return response,
+    };
+
+    match landmarks.estimate(&graph, start_actor_id, target_actor_id) {
+        Some(estimate) => HttpResponse::Ok().json(EstimateResponse { estimate: Some(estimate), error: None }),
+        None => HttpResponse::Ok().json(EstimateResponse {
+            error: Some(format!("No link found between '{}' and '{}'", start_actor_name, target_actor_name)),
+            ..Default::default()
         }),
     }
 }
 
+// Size and connectivity of the loaded graph.
+async fn get_graph_stats(graph: web::Data<ActorGraph>, components: web::Data<Components>) -> impl Responder {
+    HttpResponse::Ok().json(GraphStatsResponse {
+        actor_count: graph.actor_count(),
+        movie_count: graph.movie_count(),
+        component_count: components.count(),
+        largest_component_size: components.largest_size(),
+        component_sizes: components.size_counts(),
+    })
+}
+
+// Typeahead: actors or movies with a word starting with each word typed so far.
+async fn search(query: web::Query<SearchQuery>, db_conn: web::Data<Mutex<rusqlite::Connection>>) -> impl Responder {
+    if query.q.trim().chars().count() < MIN_SEARCH_QUERY_CHARS {
+        return HttpResponse::BadRequest().json(SearchResponse {
+            error: Some(format!("Search for at least {} characters.", MIN_SEARCH_QUERY_CHARS)),
+            ..Default::default()
+        });
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_SEARCH_RESULTS).clamp(1, MAX_SEARCH_RESULTS);
+    let conn = db_conn.lock().unwrap();
+    let response = match query.search_type {
+        SearchType::Actor => db::search_actors(&conn, &query.q, limit).map(|hits| SearchResponse {
+            actors: Some(
+                hits.into_iter()
+                    .map(|hit| ActorSearchEntry { actor_id: hit.actor_id, name: hit.name, popularity: hit.popularity, credit_count: hit.credit_count })
+                    .collect(),
+            ),
+            ..Default::default()
+        }),
+        SearchType::Movie => db::search_movies(&conn, &query.q, limit).map(|hits| SearchResponse {
+            movies: Some(
+                hits.into_iter()
+                    .map(|hit| MovieSearchEntry {
+                        movie_id: hit.movie_id,
+                        title: hit.title,
+                        release_year: hit.release_date.as_deref().and_then(parse_release_year),
+                        popularity: hit.popularity,
+                        credit_count: hit.credit_count,
+                    })
+                    .collect(),
+            ),
+            ..Default::default()
+        }),
+    };
+    match response {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => HttpResponse::InternalServerError().json(SearchResponse {
+            error: Some(format!("Database error when searching: {}", e)),
+            ..Default::default()
+        }),
+    }
+}
 
+// How SQLite runs the hot lookups, to verify they use the schema's indexes.
+// Only routed when DEBUG_ROUTES_ENV is set to 1.
+async fn get_query_plans(db_conn: web::Data<Mutex<rusqlite::Connection>>) -> impl Responder {
+    let conn = db_conn.lock().unwrap();
+    match db::lookup_query_plans(&conn) {
+        Ok(plans) => HttpResponse::Ok().json(QueryPlansResponse {
+            plans: Some(plans),
+            error: None,
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(QueryPlansResponse {
+            error: Some(format!("Database error when explaining queries: {}", e)),
+            ..Default::default()
+        }),
+    }
+}
 
 #[tokio::main] // or #[actix_web::main] if you are using that
 async fn main() -> std::io::Result<()> {
@@ -147,7 +1458,32 @@ async fn main() -> std::io::Result<()> {
     ensure_database_exists().await.expect("Failed to ensure database exists");
 
     let conn = db::establish_connection().expect("Failed to connect to database");
+    let previous_version = db::migrate(&conn).expect("Failed to migrate database");
+    if previous_version < db::SCHEMA_VERSION {
+        println!("Migrated database schema from version {} to {}", previous_version, db::SCHEMA_VERSION);
+    }
+    // Loaded once and shared read-only by every worker
+    let graph = ActorGraph::load(&conn).expect("Failed to load actor graph");
+    println!("Loaded actor graph: {} actors, {} movies", graph.actor_count(), graph.movie_count());
+    let components = Components::compute(&graph);
+    println!("Found {} connected components, the largest with {} actors", components.count(), components.largest_size());
+    // Optional: searches fall back to plain BFS without it
+    let landmarks = match LandmarkIndex::load(DEFAULT_INDEX_PATH, &graph) {
+        Ok(index) => {
+            println!("Loaded landmark index with {} landmarks", index.landmark_ids().len());
+            Some(index)
+        }
+        Err(e) => {
+            println!("No landmark index loaded ({}); using bidirectional BFS", e);
+            None
+        }
+    };
+    let co_star_degrees_data = web::Data::new(co_star_degrees(&graph)); // Per actor index, for collaborator suggestions
+    let graph_data = web::Data::new(graph);
+    let components_data = web::Data::new(components);
+    let landmarks_data = web::Data::new(landmarks);
     let db_data = web::Data::new(Mutex::new(conn));
+    let debug_routes = env::var(DEBUG_ROUTES_ENV).is_ok_and(|value| value == "1");
 
     println!("Starting Actix Web server on port 8080 - with debug prints");
     HttpServer::new(move || {
@@ -158,8 +1494,32 @@ async fn main() -> std::io::Result<()> {
 
         App::new()
             .app_data(db_data.clone()) // Share database connection
+            .app_data(graph_data.clone()) // Share the in-memory actor graph
+            .app_data(components_data.clone())
+            .app_data(landmarks_data.clone())
+            .app_data(co_star_degrees_data.clone())
             .wrap(cors) // 2. Wrap the App with the Cors middleware
             .route("/api/actor-link", web::post().to(get_actor_link)) // Your route
+            .route("/api/actor-link/via", web::post().to(get_actor_link_via))
+            .route("/api/actor-movie-link", web::post().to(get_actor_movie_link))
+            .route("/api/movie-link", web::post().to(get_movie_link))
+            .route("/api/distance-distribution", web::get().to(get_distance_distribution))
+            .route("/api/central-actors", web::get().to(get_central_actors))
+            .route("/api/graph-stats", web::get().to(get_graph_stats))
+            .route("/api/estimated-degrees", web::get().to(get_estimated_degrees))
+            .route("/api/actors/{id}/collaborators", web::get().to(get_collaborators))
+            .route("/api/actors/{id}/suggested", web::get().to(get_suggested_collaborators))
+            .route("/api/actors/{id}/community", web::get().to(get_actor_community))
+            .route("/api/communities/{id}", web::get().to(get_community))
+            .route("/api/puzzle", web::get().to(get_puzzle))
+            .route("/api/puzzle/daily", web::get().to(get_daily_puzzle))
+            .route("/api/validate-chain", web::post().to(validate_chain))
+            .route("/api/search", web::get().to(search))
+            .configure(|cfg| {
+                if debug_routes {
+                    cfg.route("/api/debug/query-plans", web::get().to(get_query_plans));
+                }
+            })
     })
     .bind("0.0.0.0:8080")?
     .run()
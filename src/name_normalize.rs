@@ -0,0 +1,80 @@
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+// Lowercases, strips diacritics and punctuation and collapses whitespace, so
+// "Penélope Cruz", "penelope  cruz" and "J.K. Simmons" / "JK Simmons" compare equal.
+// Stored in actors.name_normalized: changing it needs a migration that recomputes the column.
+pub fn normalize_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    let mut pending_space = false;
+    for c in name.nfkd().filter(|&c| !is_combining_mark(c)) {
+        if c.is_alphanumeric() {
+            if pending_space && !normalized.is_empty() {
+                normalized.push(' ');
+            }
+            pending_space = false;
+            // Letters that don't decompose into a base letter and a mark
+            match c {
+                'ø' | 'Ø' => normalized.push('o'),
+                'ł' | 'Ł' => normalized.push('l'),
+                'đ' | 'Đ' => normalized.push('d'),
+                'ß' => normalized.push_str("ss"),
+                'æ' | 'Æ' => normalized.push_str("ae"),
+                'œ' | 'Œ' => normalized.push_str("oe"),
+                _ => normalized.extend(c.to_lowercase()),
+            }
+        } else if !matches!(c, '\'' | '’' | '.') {
+            pending_space = true; // "O'Brien" stays one word, "Jean-Luc" becomes two
+        }
+    }
+    normalized
+}
+
+// Levenshtein distance in characters, or None as soon as it must exceed `max_distance`.
+pub fn edit_distance_within(a: &str, b: &str, max_distance: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max_distance {
+        return None;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, &a_char) in a.iter().enumerate() {
+        current[0] = i + 1;
+        let mut row_min = current[0];
+        for (j, &b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            row_min = row_min.min(current[j + 1]);
+        }
+        if row_min > max_distance {
+            return None;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    Some(previous[b.len()]).filter(|&distance| distance <= max_distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(normalize_name("Penélope Cruz"), "penelope cruz");
+        assert_eq!(normalize_name("  BRAD   pitt "), "brad pitt");
+        assert_eq!(normalize_name("J.K. Simmons"), normalize_name("JK Simmons"));
+        assert_eq!(normalize_name("Jean-Luc O'Brien"), "jean luc obrien");
+        assert_eq!(normalize_name("Søren Łukasz Straße"), "soren lukasz strasse");
+    }
+
+    #[test]
+    fn test_edit_distance_within() {
+        assert_eq!(edit_distance_within("brad pitt", "brad pitt", 1), Some(0));
+        assert_eq!(edit_distance_within("brad pit", "brad pitt", 1), Some(1));
+        assert_eq!(edit_distance_within("kevin spacy", "kevin spacey", 2), Some(1));
+        assert_eq!(edit_distance_within("morgan freemna", "morgan freeman", 2), Some(2));
+        assert_eq!(edit_distance_within("tim robbins", "tom hanks", 3), None);
+        assert_eq!(edit_distance_within("edward", "edward norton", 3), None);
+    }
+}
@@ -0,0 +1,88 @@
+use crate::db;
+use crate::name_normalize::normalize_name;
+use rusqlite::{Connection, Result};
+
+pub const DEFAULT_CANDIDATE_COUNT: usize = 5;
+
+// An actor a typed name may refer to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameCandidate {
+    pub actor_id: i64,
+    pub name: String,
+    pub distance: usize, // Edits between the normalized names; 0 when they're equal
+    pub credit_count: usize,
+}
+
+// Typos allowed for a normalized name: one per five characters, between one and three.
+fn max_edits(normalized: &str) -> usize {
+    (normalized.chars().count() / 5).clamp(1, 3)
+}
+
+// Every actor the typed name names exactly, busiest first: actors with exactly this
+// name if there are any, else those matching it ignoring case, accents and punctuation.
+// Unlike `resolve_actor_name` this never looks for typos.
+pub fn exact_actor_matches(conn: &Connection, name: &str) -> Result<Vec<NameCandidate>> {
+    let mut matches = db::get_actors_by_name(conn, name)?;
+    if matches.is_empty() {
+        matches = db::get_actors_by_normalized_name(conn, &normalize_name(name))?;
+    }
+    ranked_candidates(conn, matches.into_iter().map(|(actor_id, name)| (actor_id, name, 0)).collect())
+}
+
+// Actors a typed name may refer to, best first: the exact matches if there are any,
+// else names within a few typos. Ties go to the actor with more credits.
+pub fn resolve_actor_name(conn: &Connection, name: &str, limit: usize) -> Result<Vec<NameCandidate>> {
+    let mut candidates = exact_actor_matches(conn, name)?;
+    let normalized = normalize_name(name);
+    if candidates.is_empty() && !normalized.is_empty() {
+        candidates = ranked_candidates(conn, db::find_actors_near_normalized_name(conn, &normalized, max_edits(&normalized))?)?;
+    }
+    candidates.truncate(limit);
+    Ok(candidates)
+}
+
+// (actor_id, name, distance) matches as candidates, closest and then busiest first.
+fn ranked_candidates(conn: &Connection, matches: Vec<(i64, String, usize)>) -> Result<Vec<NameCandidate>> {
+    let mut candidates = matches
+        .into_iter()
+        .map(|(actor_id, name, distance)| Ok(NameCandidate { actor_id, name, distance, credit_count: db::get_credit_count(conn, actor_id)? }))
+        .collect::<Result<Vec<NameCandidate>>>()?;
+    candidates.sort_by(|a, b| a.distance.cmp(&b.distance).then(b.credit_count.cmp(&a.credit_count)).then(a.actor_id.cmp(&b.actor_id)));
+    Ok(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[test]
+    fn test_resolve_actor_name() -> Result<()> {
+        let conn = test_support::fixture_connection()?;
+        db::insert_actor(&conn, 955, "Penélope Cruz", "Acting")?;
+        let best = |name: &str| resolve_actor_name(&conn, name, DEFAULT_CANDIDATE_COUNT).map(|candidates| candidates.first().map(|c| (c.actor_id, c.distance)));
+
+        assert_eq!(best("Brad Pitt")?, Some((2, 0)));
+        assert_eq!(best("brad pitt")?, Some((2, 0)));
+        assert_eq!(best("Penelope Cruz")?, Some((14, 0)));
+        assert_eq!(best("Benicio Del Toro")?, Some((8, 0)));
+        // Typos only ever suggest, they don't resolve
+        assert_eq!(best("Edward Nortn")?, Some((1, 1)));
+        assert!(exact_actor_matches(&conn, "Edward Nortn")?.is_empty());
+        assert_eq!(best("Nobody At All")?, None);
+        assert_eq!(best("")?, None);
+
+        // Edward Norton and Edward Furlong are equally far from this; Norton has more credits
+        let candidates = resolve_actor_name(&conn, "Edward Nurlon", DEFAULT_CANDIDATE_COUNT)?;
+        let ids: Vec<i64> = candidates.iter().map(|candidate| candidate.actor_id).collect();
+        assert_eq!(ids, vec![1, 9]);
+        assert_eq!(candidates[0].credit_count, 4);
+
+        // Two people sharing a name both resolve exactly, the busier one first
+        db::insert_actor(&conn, 91001, "Brad Pitt", "Acting")?;
+        let candidates = resolve_actor_name(&conn, "brad pitt", DEFAULT_CANDIDATE_COUNT)?;
+        let ids: Vec<(i64, usize)> = candidates.iter().map(|candidate| (candidate.actor_id, candidate.distance)).collect();
+        assert_eq!(ids, vec![(2, 0), (15, 0)]);
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use crate::db;
+use crate::graph::ActorGraph;
+use crate::landmarks::{LandmarkIndex, DEFAULT_INDEX_PATH, DEFAULT_LANDMARK_COUNT};
+use crate::tmdb_get::{get_movie_credits, get_movie_details, get_person_details_with_client, movie_exists_with_client, is_feature_film_with_client, TMDBCredit};
+use rusqlite::Result;
+use std::env;
+use futures::stream::{self, StreamExt};
+
+pub async fn populate_database() -> Result<(), Box<dyn std::error::Error>> {
+    let api_key = env::var("TMDB_API_KEY")?;
+    let mut conn = db::establish_connection()?;
+    db::setup_database(&conn)?;
+
+    // Create reusable client
+    let client = reqwest::Client::new();
+    let concurrent_requests = 10;
+
+    // Use transaction for batch inserts
+    let tx = conn.transaction()?;
+
+    let movie_ids: Vec<u32> = (262000..302000).collect();
+
+    let mut stream = stream::iter(movie_ids)
+        .map(|movie_tmdb_id| {
+            let api_key = api_key.clone();
+            let client = client.clone();
+            async move {
+                //sleep(Duration::from_millis(10)).await;
+
+                match movie_exists_with_client(movie_tmdb_id, &api_key, &client).await {
+                    Ok(true) => {
+                        match is_feature_film_with_client(movie_tmdb_id, &api_key, &client).await {
+                            Ok(true) => {
+                                match get_movie_credits(movie_tmdb_id, &api_key).await {
+                                    Ok(movie_credits) => {
+                                        println!("Processing feature film ID: {}", movie_tmdb_id);
+                                        Some((movie_tmdb_id, movie_credits))
+                                    }
+                                    Err(e) => {
+                                        eprintln!("Error fetching credits for movie ID {}: {}", movie_tmdb_id, e);
+                                        None
+                                    }
+                                }
+                            }
+                            Ok(false) => {
+                                println!("Skipping non-feature film ID: {}", movie_tmdb_id);
+                                None
+                            }
+                            Err(e) => {
+                                eprintln!("Error checking movie type for ID {}: {}", movie_tmdb_id, e);
+                                None
+                            }
+                        }
+                    }
+                    Ok(false) => {
+                        println!("Movie ID {} does not exist", movie_tmdb_id);
+                        None
+                    }
+                    Err(e) => {
+                        eprintln!("Error checking if movie {} exists: {}", movie_tmdb_id, e);
+                        None
+                    }
+                }
+            }
+        })
+        .buffer_unordered(concurrent_requests);
+
+    // Process results in batches
+    let mut batch = Vec::new();
+    while let Some(result) = stream.next().await {
+        if let Some(data) = result {
+            batch.push(data);
+            if batch.len() >= 50 {
+                process_batch(&tx, &batch).await?;
+                batch.clear();
+            }
+        }
+    }
+
+    // Process remaining items
+    if !batch.is_empty() {
+        process_batch(&tx, &batch).await?;
+    }
+
+    tx.commit()?;
+    println!("Database populated with feature film and actor data.");
+
+    // The landmark index describes the old graph once credits change
+    let graph = ActorGraph::load(&conn)?;
+    LandmarkIndex::build(&graph, DEFAULT_LANDMARK_COUNT).save(DEFAULT_INDEX_PATH)?;
+    println!("Rebuilt landmark index at {}.", DEFAULT_INDEX_PATH);
+    Ok(())
+}
+
+// Optional pass that fetches profile images, popularity and biographical details for
+// actors not looked up yet, so it can be stopped and resumed. `limit` caps the lookups.
+pub async fn enrich_actors(limit: Option<usize>) -> Result<(), Box<dyn std::error::Error>> {
+    let api_key = env::var("TMDB_API_KEY")?;
+    let conn = db::establish_connection()?;
+    db::setup_database(&conn)?;
+
+    let client = reqwest::Client::new();
+    let concurrent_requests = 10;
+    let tmdb_actor_ids = db::get_tmdb_actor_ids_without_details(&conn, limit)?;
+    println!("Fetching details for {} actors.", tmdb_actor_ids.len());
+
+    let mut stream = stream::iter(tmdb_actor_ids)
+        .map(|tmdb_actor_id| {
+            let api_key = api_key.clone();
+            let client = client.clone();
+            async move {
+                match get_person_details_with_client(tmdb_actor_id, &api_key, &client).await {
+                    Ok(person) => Some(person),
+                    Err(e) => {
+                        eprintln!("Error fetching details for person ID {}: {}", tmdb_actor_id, e);
+                        None // Left for the next run
+                    }
+                }
+            }
+        })
+        .buffer_unordered(concurrent_requests);
+
+    // Commit every 50 actors so an interrupted run keeps its progress
+    let mut tx = conn.unchecked_transaction()?;
+    let mut pending = 0;
+    let mut enriched = 0;
+    while let Some(result) = stream.next().await {
+        let Some(person) = result else {
+            continue;
+        };
+        db::update_actor_details(&tx, person.id, &db::ActorDetails {
+            profile_path: person.profile_path.as_deref(),
+            popularity: person.popularity,
+            gender: person.gender,
+            birthday: person.birthday.as_deref(),
+            deathday: person.deathday.as_deref(),
+            place_of_birth: person.place_of_birth.as_deref(),
+        })?;
+        enriched += 1;
+        pending += 1;
+        if pending >= 50 {
+            tx.commit()?;
+            tx = conn.unchecked_transaction()?;
+            pending = 0;
+        }
+    }
+    tx.commit()?;
+    println!("Stored details for {} actors.", enriched);
+    Ok(())
+}
+
+async fn process_batch<'a>(tx: &'a rusqlite::Transaction<'a>, batch: &[(u32, TMDBCredit)]) -> Result<(), Box<dyn std::error::Error>> {
+    for (movie_tmdb_id, movie_credits) in batch {
+        let movie_details = get_movie_details(*movie_tmdb_id, &env::var("TMDB_API_KEY")?).await?;
+        db::insert_movie(tx, &db::NewMovie {
+            tmdb_movie_id: *movie_tmdb_id,
+            title: &movie_details.title,
+            release_date: movie_details.release_date.as_deref(),
+            popularity: movie_details.popularity,
+            vote_count: movie_details.vote_count,
+            runtime: movie_details.runtime,
+            poster_path: movie_details.poster_path.as_deref(),
+        })?;
+
+        let mut stmt = tx.prepare("SELECT movie_id FROM movies WHERE tmdb_movie_id = ?")?;
+        let mut rows = stmt.query([movie_tmdb_id])?;
+        let movie_id: i64 = rows.next()?.unwrap().get(0)?;
+
+        for genre in &movie_details.genres {
+            db::insert_genre(tx, genre.id, &genre.name)?;
+            db::insert_movie_genre(tx, movie_id, genre.id)?;
+        }
+
+        for actor in &movie_credits.cast {
+            db::insert_actor(tx, actor.id, &actor.name, &actor.known_for_department)?;
+            let mut actor_stmt = tx.prepare("SELECT actor_id FROM actors WHERE tmdb_actor_id = ?")?;
+            let mut actor_rows = actor_stmt.query([actor.id])?;
+            let actor_id: i64 = actor_rows.next()?.unwrap().get(0)?;
+            let character = actor.character.as_deref().filter(|character| !character.is_empty());
+            db::insert_movie_actor_link(tx, movie_id, actor_id, actor.order, character)?;
+        }
+    }
+    Ok(())
+}
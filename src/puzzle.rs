@@ -0,0 +1,115 @@
+use crate::graph::{ActorGraph, UNKNOWN_ORDER};
+use crate::link_finder::actor_distances;
+use chrono::{Datelike, NaiveDate};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+// How many of the best-known actors count as popular.
+pub const POPULAR_ACTOR_COUNT: usize = 1_000;
+// Start actors tried before giving up on finding a pair at the requested distance.
+const MAX_START_ATTEMPTS: usize = 20;
+// Credits billed below this position don't make an actor well known.
+const FAME_MAX_CAST_ORDER: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Puzzle {
+    pub start_actor_id: i64,
+    pub target_actor_id: i64,
+    pub degrees: u32,
+}
+
+// Picks two actors whose shortest chain is exactly `degrees` hops. The same seed
+// always gives the same puzzle for the same graph. With `popular_only`, both
+// actors come from the POPULAR_ACTOR_COUNT best-known actors.
+pub fn generate_puzzle(graph: &ActorGraph, degrees: u32, seed: u64, popular_only: bool) -> Option<Puzzle> {
+    let candidates = if popular_only { popular_actors(graph, POPULAR_ACTOR_COUNT) } else { (0..graph.actor_count()).collect() };
+    let mut is_candidate = vec![false; graph.actor_count()];
+    for &actor in &candidates {
+        is_candidate[actor] = true;
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut starts = candidates;
+    starts.shuffle(&mut rng);
+    for &start in starts.iter().take(MAX_START_ATTEMPTS) {
+        let targets: Vec<usize> = actor_distances(graph, start)
+            .into_iter()
+            .enumerate()
+            .filter(|&(actor, distance)| distance == degrees && is_candidate[actor])
+            .map(|(actor, _)| actor)
+            .collect();
+        if !targets.is_empty() {
+            let target = targets[rng.gen_range(0..targets.len())];
+            return Some(Puzzle { start_actor_id: graph.actor_id(start), target_actor_id: graph.actor_id(target), degrees });
+        }
+    }
+    None
+}
+
+// Seed for the daily challenge, so everyone playing on a date gets the same puzzle.
+pub fn daily_seed(date: NaiveDate) -> u64 {
+    date.num_days_from_ce() as u64
+}
+
+// Actor indices ranked by fame: the summed popularity of movies they were billed near the top of.
+fn popular_actors(graph: &ActorGraph, count: usize) -> Vec<usize> {
+    let mut fame: Vec<(f64, usize)> = (0..graph.actor_count())
+        .map(|actor| {
+            let score = graph
+                .movies_of(actor)
+                .iter()
+                .zip(graph.movie_orders_of(actor))
+                .filter(|&(_, &cast_order)| cast_order != UNKNOWN_ORDER && cast_order <= FAME_MAX_CAST_ORDER)
+                .filter_map(|(&movie, _)| graph.movie_info(movie as usize).popularity)
+                .sum::<f64>();
+            (score, actor)
+        })
+        .filter(|&(score, _)| score > 0.0)
+        .collect();
+    fame.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+    fame.truncate(count);
+    let mut actors: Vec<usize> = fame.into_iter().map(|(_, actor)| actor).collect();
+    actors.sort_unstable(); // Keep the shuffle independent of fame ties
+    actors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::link_finder::find_actor_link_bidirectional_bfs;
+    use crate::test_support;
+    use rusqlite::Result;
+
+    #[test]
+    fn test_generate_puzzle() -> Result<()> {
+        let conn = test_support::fixture_connection()?;
+        let graph = ActorGraph::load(&conn)?;
+        for seed in 0..10 {
+            let puzzle = generate_puzzle(&graph, 3, seed, false).unwrap();
+            let path = find_actor_link_bidirectional_bfs(&graph, puzzle.start_actor_id, puzzle.target_actor_id).unwrap();
+            assert_eq!(path.len(), 4);
+            assert_eq!(generate_puzzle(&graph, 3, seed, false), Some(puzzle));
+        }
+        // Nobody is more than four hops apart (del Toro to Gere or Furlong)
+        assert_eq!(generate_puzzle(&graph, 5, 0, false), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_popular_only_puzzle() -> Result<()> {
+        let conn = test_support::fixture_connection()?;
+        let graph = ActorGraph::load(&conn)?;
+        // Meat Loaf is billed fourth in his only movie, so he never counts as popular
+        let popular = popular_actors(&graph, POPULAR_ACTOR_COUNT);
+        assert!(popular.contains(&graph.actor_index(7).unwrap()));
+        assert!(!popular.contains(&graph.actor_index(4).unwrap()));
+        for seed in 0..10 {
+            let puzzle = generate_puzzle(&graph, 3, seed, true).unwrap();
+            assert_ne!(puzzle.start_actor_id, 4);
+            assert_ne!(puzzle.target_actor_id, 4);
+        }
+        assert_eq!(daily_seed(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()), daily_seed(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()) + 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use crate::db;
+use rusqlite::{Connection, Result};
+
+// Actors in insertion order, so actor_id = position + 1.
+const ACTORS: &[(u32, &str)] = &[
+    (819, "Edward Norton"),         // 1
+    (287, "Brad Pitt"),             // 2
+    (1283, "Helena Bonham Carter"), // 3
+    (7470, "Meat Loaf"),            // 4
+    (192, "Morgan Freeman"),        // 5
+    (1979, "Kevin Spacey"),         // 6
+    (1121, "Gabriel Byrne"),        // 7
+    (1371, "Benicio del Toro"),     // 8
+    (20220, "Edward Furlong"),      // 9
+    (504, "Tim Robbins"),           // 10
+    (1205, "Richard Gere"),         // 11
+    (90001, "Island Actor One"),    // 12
+    (90002, "Island Actor Two"),    // 13
+];
+
+const GENRES: &[(u32, &str)] = &[(18, "Drama"), (28, "Action"), (53, "Thriller"), (80, "Crime"), (878, "Science Fiction")];
+
+struct FixtureMovie {
+    tmdb_movie_id: u32,
+    title: &'static str,
+    release_date: &'static str,
+    popularity: f64,
+    genre_ids: &'static [u32],
+    cast: &'static [i64], // actor_ids in billing order
+}
+
+const fn movie(
+    tmdb_movie_id: u32,
+    title: &'static str,
+    release_date: &'static str,
+    popularity: f64,
+    genre_ids: &'static [u32],
+    cast: &'static [i64],
+) -> FixtureMovie {
+    FixtureMovie { tmdb_movie_id, title, release_date, popularity, genre_ids, cast }
+}
+
+// Movies in insertion order, so movie_id = position + 1.
+const MOVIES: &[FixtureMovie] = &[
+    movie(550, "Fight Club", "1999-10-15", 61.4, &[18], &[1, 2, 3, 4]),              // 1
+    movie(807, "Se7en", "1995-09-22", 48.2, &[80, 53], &[2, 5, 6]),                  // 2
+    movie(629, "The Usual Suspects", "1995-07-19", 27.9, &[80, 53], &[6, 7, 8]),     // 3
+    movie(73, "American History X", "1998-10-30", 25.1, &[18], &[1, 9]),             // 4
+    movie(278, "The Shawshank Redemption", "1994-09-23", 88.6, &[18, 80], &[10, 5]), // 5
+    movie(1592, "Primal Fear", "1996-04-03", 15.3, &[80, 18, 53], &[11, 1]),         // 6
+    movie(1724, "The Incredible Hulk", "2008-06-12", 0.6, &[878, 28], &[1, 10]),     // 7
+    movie(99999, "Island Picture", "2001-01-01", 0.1, &[18], &[12, 13]),             // 8
+];
+
+// (movie_id, actor_id, character); other credits have no character, as TMDB sometimes does.
+const CHARACTERS: &[(i64, i64, &str)] = &[
+    (1, 1, "The Narrator"),
+    (1, 2, "Tyler Durden"),
+    (1, 3, "Marla Singer"),
+    (1, 4, "Robert 'Bob' Paulson"),
+    (2, 2, "David Mills"),
+    (2, 5, "William Somerset"),
+    (2, 6, "John Doe"),
+];
+
+// Small in-memory database with a known cast graph:
+// Norton -> Pitt -> Spacey -> Byrne is the only 3-hop chain to Byrne,
+// Norton reaches Freeman through both Pitt and Robbins,
+// and actors 12/13 form a separate island.
+pub fn fixture_connection() -> Result<Connection> {
+    let conn = Connection::open_in_memory()?;
+    db::setup_database(&conn)?;
+    for (tmdb_actor_id, name) in ACTORS {
+        db::insert_actor(&conn, *tmdb_actor_id, name, "Acting")?;
+    }
+    for (genre_id, name) in GENRES {
+        db::insert_genre(&conn, *genre_id, name)?;
+    }
+    for (movie_index, movie) in MOVIES.iter().enumerate() {
+        let movie_id = movie_index as i64 + 1;
+        db::insert_movie(&conn, &db::NewMovie {
+            tmdb_movie_id: movie.tmdb_movie_id,
+            title: movie.title,
+            release_date: Some(movie.release_date),
+            popularity: Some(movie.popularity),
+            ..Default::default()
+        })?;
+        for genre_id in movie.genre_ids {
+            db::insert_movie_genre(&conn, movie_id, *genre_id)?;
+        }
+        for (cast_order, actor_id) in movie.cast.iter().enumerate() {
+            let character = CHARACTERS.iter().find(|&&(m, a, _)| m == movie_id && a == *actor_id).map(|&(_, _, character)| character);
+            db::insert_movie_actor_link(&conn, movie_id, *actor_id, Some(cast_order as u32), character)?;
+        }
+    }
+    Ok(conn)
+}
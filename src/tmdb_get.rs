@@ -1,4 +1,3 @@
-use reqwest;
 use std::time::Duration;
 use std::future::Future;
 use tokio::time::sleep;
@@ -8,6 +7,8 @@ pub struct TMDBPerson {
     pub id: u32,
     pub name: String,
     pub known_for_department: String,
+    pub order: Option<u32>, // Billing position in the movie's cast
+    pub character: Option<String>, // Role played; TMDB sends "" when unknown
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -33,8 +34,26 @@ pub struct TMDBMovie {
     pub release_date: Option<String>,
     pub video: bool,
     pub genres: Vec<Genre>,
+    pub popularity: Option<f64>,
+    pub vote_count: Option<u32>,
+    pub runtime: Option<u32>, // Minutes
+    pub poster_path: Option<String>, // Relative to TMDB's image base URL, e.g. "/pB8BM7pdSp6B6Ih7QZ4DrQ3PmJK.jpg"
 }
 
+// From the person endpoint; the credits list only carries the name.
+#[derive(Debug, serde::Deserialize)]
+pub struct TMDBPersonDetails {
+    pub id: u32,
+    pub name: String,
+    pub profile_path: Option<String>, // Relative to TMDB's image base URL
+    pub popularity: Option<f64>,
+    pub gender: Option<u8>, // 0 not set, 1 female, 2 male, 3 non-binary
+    pub birthday: Option<String>, // "YYYY-MM-DD"
+    pub deathday: Option<String>,
+    pub place_of_birth: Option<String>,
+}
+
+#[allow(dead_code)] // Called from the commented-out line in get_movie_credits when debugging
 async fn debug_log_response(body_text: &str, movie_id: u32) -> Result<(), reqwest::Error> {
     eprintln!(
         "Raw response body for movie ID {}:\n{}",
@@ -90,6 +109,7 @@ pub async fn movie_exists(
     Ok(response.status().is_success())
 }
 
+#[allow(dead_code)] // Kept for wrapping TMDB calls in retries
 async fn with_retry<F, Fut, T>(f: F) -> Result<T, Box<dyn std::error::Error>>
 where
     F: Fn() -> Fut,
@@ -137,6 +157,20 @@ pub async fn get_movie_details_with_client(
     Ok(serde_json::from_str(&body_text)?)
 }
 
+pub async fn get_person_details_with_client(
+    person_id: u32,
+    api_key: &str,
+    client: &reqwest::Client,
+) -> Result<TMDBPersonDetails, Box<dyn std::error::Error>> {
+    let url = format!(
+        "https://api.themoviedb.org/3/person/{}?api_key={}",
+        person_id, api_key
+    );
+    let response = client.get(&url).send().await?;
+    let body_text = response.text().await?;
+    Ok(serde_json::from_str(&body_text)?)
+}
+
 pub async fn is_feature_film_with_client(
     movie_id: u32,
     api_key: &str,